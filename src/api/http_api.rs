@@ -0,0 +1,175 @@
+use std::io;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::thread::spawn;
+use std::time::Duration;
+
+use serde_json;
+use serde_json::Value;
+
+use eew::EEW_JSON_VERSION;
+use api::TinaState;
+use translator::format_eew_json;
+use metrics::Metrics;
+
+const READ_TIMEOUT_SECS: u64 = 5;
+const MAX_HEADER_COUNT: usize = 64;
+const JSON_CONTENT_TYPE: &'static str = "application/json";
+const METRICS_CONTENT_TYPE: &'static str = "text/plain; version=0.0.4";
+
+// a read-only HTTP API exposing TinaState as JSON and Metrics for Prometheus
+pub struct HttpApi {
+	addr: SocketAddr,
+}
+
+struct Response {
+	status: u16,
+	reason: &'static str,
+	content_type: &'static str,
+	body: String,
+}
+
+fn respond(status: u16, reason: &'static str, body: Value) -> Response
+{
+	Response { status: status, reason: reason, content_type: JSON_CONTENT_TYPE, body: body.to_string() }
+}
+
+fn not_found() -> Response
+{
+	respond(404, "Not Found", json!({ "error": "not found" }))
+}
+
+fn latest(state: &TinaState) -> Response
+{
+	let latest = match state.history.latest() {
+		Some(eew) => eew,
+		None => return not_found(),
+	};
+
+	let reports = state.history.get(&latest.id).unwrap_or(&[]);
+	let prev = match reports.len() {
+		n if n >= 2 => Some(reports[n - 2].as_ref()),
+		_ => None,
+	};
+
+	respond(200, "OK", format_eew_json(latest, prev))
+}
+
+fn reports(state: &TinaState, id: &str) -> Response
+{
+	let reports = match state.history.get(id) {
+		Some(r) => r,
+		None => return not_found(),
+	};
+
+	let values: Vec<Value> = reports.iter()
+		.map(|eew| serde_json::to_value(eew.as_ref()).expect("EEW is always serializable"))
+		.collect();
+
+	respond(200, "OK", json!({
+		"version": EEW_JSON_VERSION,
+		"id": id,
+		"reports": values,
+	}))
+}
+
+fn health(state: &TinaState) -> Response
+{
+	let connected = state.connections.iter().filter(|c| c.connected).count();
+	let body = json!({
+		"status": if connected > 0 { "ok" } else { "unavailable" },
+		"connected": connected,
+		"connections": state.connections,
+	});
+
+	match connected {
+		0 => respond(503, "Service Unavailable", body),
+		_ => respond(200, "OK", body),
+	}
+}
+
+fn route(method: &str, path: &str, state: &Mutex<TinaState>) -> Response
+{
+	if method != "GET" {
+		return respond(405, "Method Not Allowed", json!({ "error": "method not allowed" }));
+	}
+
+	// query strings are not used by any endpoint
+	let path = path.split('?').next().unwrap_or("");
+
+	if path == "/metrics" {
+		return Response { status: 200, reason: "OK", content_type: METRICS_CONTENT_TYPE, body: Metrics::render() };
+	}
+
+	let state = state.lock().unwrap();
+
+	match path {
+		"/health" => health(&state),
+		"/eew/latest" => latest(&state),
+		p if p.starts_with("/eew/") && p.len() > "/eew/".len() => reports(&state, &p["/eew/".len()..]),
+		_ => not_found(),
+	}
+}
+
+fn serve(stream: TcpStream, state: Arc<Mutex<TinaState>>) -> io::Result<()>
+{
+	stream.set_read_timeout(Some(Duration::from_secs(READ_TIMEOUT_SECS)))?;
+
+	let mut reader = BufReader::new(stream.try_clone()?);
+	let mut stream = stream;
+
+	let mut request_line = String::new();
+	reader.read_line(&mut request_line)?;
+
+	for _ in 0..MAX_HEADER_COUNT {
+		let mut line = String::new();
+		if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+			break;
+		}
+	}
+
+	let mut parts = request_line.split_whitespace();
+	let response = match (parts.next(), parts.next()) {
+		(Some(method), Some(path)) => route(method, path, &state),
+		_ => respond(400, "Bad Request", json!({ "error": "bad request" })),
+	};
+
+	let head = format!("\
+		HTTP/1.1 {} {}\r\n\
+		Content-Type: {}\r\n\
+		Content-Length: {}\r\n\
+		Connection: close\r\n\r\n",
+		response.status, response.reason, response.content_type, response.body.len());
+
+	stream.write_all(head.as_bytes())?;
+	stream.write_all(response.body.as_bytes())?;
+	stream.flush()
+}
+
+impl HttpApi {
+
+	pub fn start(listen_addr: &str, state: Arc<Mutex<TinaState>>) -> io::Result<HttpApi>
+	{
+		let listener = TcpListener::bind(listen_addr)?;
+		let addr = listener.local_addr()?;
+
+		spawn(move || {
+			for stream in listener.incoming().filter_map(|s| s.ok()) {
+				let state = state.clone();
+				spawn(move || {
+					if let Err(e) = serve(stream, state) {
+						debug!("HttpApi: connection error ({})", e);
+					}
+				});
+			}
+		});
+
+		Ok(HttpApi { addr: addr })
+	}
+
+	pub fn local_addr(&self) -> SocketAddr
+	{
+		self.addr
+	}
+}
@@ -0,0 +1,5 @@
+mod state;
+mod http_api;
+
+pub use self::state::{TinaState, ConnectionStatus};
+pub use self::http_api::HttpApi;
@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+
+use collections::EEWHistory;
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ConnectionStatus {
+	pub connected: bool,
+	pub server: Option<String>,
+	pub connected_at: Option<DateTime<Utc>>,
+	pub last_received_at: Option<DateTime<Utc>>,
+	pub last_error: Option<String>,
+}
+
+// the state shared between the main loop, WNI threads and HttpApi
+#[derive(Debug, Clone)]
+pub struct TinaState {
+	pub history: EEWHistory,
+	pub connections: Vec<ConnectionStatus>,
+}
+
+impl ConnectionStatus {
+
+	pub fn set_connected(&mut self, server: String)
+	{
+		*self = ConnectionStatus {
+			connected: true,
+			server: Some(server),
+			connected_at: Some(Utc::now()),
+			last_received_at: None,
+			last_error: None,
+		};
+	}
+
+	pub fn set_received(&mut self)
+	{
+		self.last_received_at = Some(Utc::now());
+	}
+
+	pub fn set_error(&mut self, error: String)
+	{
+		self.connected = false;
+		self.last_error = Some(error);
+	}
+}
+
+impl TinaState {
+
+	pub fn new(history: EEWHistory, connection_count: usize) -> TinaState
+	{
+		TinaState { history: history, connections: vec![ConnectionStatus::default(); connection_count] }
+	}
+}
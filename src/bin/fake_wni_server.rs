@@ -0,0 +1,77 @@
+extern crate tina;
+
+use std::env;
+use std::fs::File;
+use std::io::Read;
+use std::thread::sleep;
+use std::time::Duration;
+
+use tina::*;
+
+const KEEP_ALIVE_INTERVAL_SECS: u64 = 30;
+const USAGE: &'static str = "Usage: fake_wni_server <wni_id> <wni_password> [telegram_file] \
+	[--interval <secs>] [--delay <ms>]";
+
+// serves a fake WNI server list and FastCaster server on localhost;
+// telegrams in the given file are pushed to each connection at the given interval
+fn main()
+{
+	let args: Vec<String> = env::args().collect();
+
+	if args.len() < 3 {
+		eprintln!("{}", USAGE);
+		return;
+	}
+
+	let mut telegram_path = None;
+	let mut interval_secs = 10;
+	let mut delay_ms = 0;
+	let mut it = args[3..].iter();
+
+	while let Some(arg) = it.next() {
+		match (arg.as_str(), it.clone().next().map(|v| v.parse::<i64>())) {
+			("--interval", Some(Ok(v))) if v >= 0 => { interval_secs = v as u64; it.next(); },
+			("--delay", Some(Ok(v))) => { delay_ms = v; it.next(); },
+			(path, _) if ! path.starts_with("--") && telegram_path.is_none() => telegram_path = Some(path.to_owned()),
+			_ => {
+				eprintln!("{}", USAGE);
+				return;
+			}
+		}
+	}
+
+	let mut telegrams = Vec::new();
+
+	if let Some(ref path) = telegram_path {
+		let mut data = Vec::new();
+		if let Err(e) = File::open(path).and_then(|mut f| f.read_to_end(&mut data)) {
+			eprintln!("Error while reading '{}' ({})", path, e);
+			return;
+		}
+		telegrams = extract_telegrams(&data);
+	}
+
+	let script: Vec<FakeWniEvent> = telegrams.into_iter().flat_map(|t| vec![
+		FakeWniEvent::Wait(Duration::from_secs(interval_secs)),
+		FakeWniEvent::Telegram { data: t, delay_ms: delay_ms },
+	]).collect();
+
+	let scripts = (0..1024).map(|_| script.clone()).collect();
+	let keep_alive = Some(Duration::from_secs(KEEP_ALIVE_INTERVAL_SECS));
+
+	let server = match FakeWniServer::start(&args[1], &args[2], scripts, keep_alive) {
+		Ok(s) => s,
+		Err(e) => {
+			eprintln!("Error while starting server ({})", e);
+			return;
+		}
+	};
+
+	println!("server list: {}", server.server_list_url());
+	println!("fast caster: {}", server.fast_caster_addr());
+
+	loop {
+		sleep(Duration::from_secs(60));
+		eprintln!("{:?}", server.stats());
+	}
+}
@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+use std::net::ToSocketAddrs;
+use std::time::Duration;
+
+use destination::{Twitter, Slack, PushBullet, Discord, Mqtt, Webhook};
+use translator::{Language, NameDict, Translator};
+use observer::Observer;
+use config::{Config, ConditionConfig, ValueConditionConfig};
+
+// validation for `tina check-config`; unlike `build_routers`, nothing is started here
+
+#[derive(Debug, Default)]
+pub struct CheckReport {
+	pub errors: Vec<String>,
+	pub warnings: Vec<String>,
+}
+
+impl CheckReport {
+
+	fn error(&mut self, msg: String)
+	{
+		self.errors.push(msg);
+	}
+
+	fn warn(&mut self, msg: String)
+	{
+		self.warnings.push(msg);
+	}
+}
+
+fn is_http_url(url: &str) -> bool
+{
+	url.starts_with("http://") || url.starts_with("https://")
+}
+
+// names missing in the companion dictionaries are left in Japanese
+fn check_language(report: &mut CheckReport, key: &str, language: Language, en_names: &NameDict)
+{
+	if language == Language::English && (en_names.epicenter.is_empty() || en_names.area.is_empty()) {
+		report.warn(format!("{}.language: `path.epicenter_en` or `path.area_en` is not set; \
+			epicenter and area names are left in Japanese", key));
+	}
+}
+
+fn check_url(report: &mut CheckReport, key: &str, url: &str, valid: bool)
+{
+	if ! valid || ! is_http_url(url) {
+		report.error(format!("{}: invalid url `{}`", key, url));
+	}
+}
+
+fn check_listen_addr(report: &mut CheckReport, key: &str, addr: &str)
+{
+	if addr.to_socket_addrs().map(|mut a| a.next().is_none()).unwrap_or(true) {
+		report.error(format!("{}: invalid address `{}`", key, addr));
+	}
+}
+
+// from unknown to 7 via 0, 1, 2, 3, 4, 5-, 5+, 6- and 6+
+const MAX_INTENSITY_STEPS: u8 = 10;
+
+// reasons why all EEWs are rejected by the condition
+fn contradictions(v: &ValueConditionConfig) -> Vec<&'static str>
+{
+	let mut reasons = Vec::new();
+
+	let needs_prev = v.phase_changed.is_some() || v.epicenter_name_changed.is_some() ||
+		v.intensity_up.is_some() || v.intensity_down.is_some();
+	let needs_detail = v.magnitude_over.is_some() || v.intensity_over.is_some() ||
+		v.epicenter_name_changed.is_some() || v.intensity_up.is_some() || v.intensity_down.is_some() ||
+		v.areas.is_some() || v.area_intensity_over.is_some() || v.area_alert.is_some() || v.area_wave_status.is_some() ||
+		v.distance_under_km.is_some() || v.s_wave_within_secs.is_some() || v.estimated_intensity_at_site_over.is_some();
+
+	if v.first.is_some() && v.first == v.succeeding {
+		reasons.push("`first` and `succeeding` are exclusive");
+	}
+	if (v.first == Some(true) || v.succeeding == Some(false)) && needs_prev {
+		reasons.push("comparisons with the previous EEW never match the first EEW");
+	}
+	if v.cancel == Some(true) && v.alert == Some(true) {
+		reasons.push("a cancel EEW is never an alert");
+	}
+	if v.cancel == Some(true) && needs_detail {
+		reasons.push("a cancel EEW has no details");
+	}
+	if v.intensity_up.unwrap_or(0) > 0 && v.intensity_down.unwrap_or(0) > 0 {
+		reasons.push("`intensity_up` and `intensity_down` are exclusive");
+	}
+	if v.intensity_up.unwrap_or(0) > MAX_INTENSITY_STEPS || v.intensity_down.unwrap_or(0) > MAX_INTENSITY_STEPS {
+		reasons.push("the maximum intensity never changes by more than 10 classes");
+	}
+	if v.areas.as_ref().is_some_and(|a| a.is_empty()) {
+		reasons.push("`areas` is empty");
+	}
+
+	reasons
+}
+
+// a clause which never matches always matches under an odd number of `not`
+fn warn_never_matches(report: &mut CheckReport, key: &str, negated: bool, reason: &str)
+{
+	match negated {
+		false => report.warn(format!("{}: never matches ({})", key, reason)),
+		true => report.warn(format!("{}: always matches under `not` ({})", key, reason)),
+	}
+}
+
+fn check_observers(report: &mut CheckReport, key: &str, v: &ValueConditionConfig, observers: &[Observer],
+	negated: bool)
+{
+	for name in v.observers.iter().flat_map(|o| o.iter()) {
+		if ! observers.iter().any(|o| o.name == *name) {
+			report.warn(format!("{}.observers: unknown observer `{}`", key, name));
+		}
+	}
+
+	let has_observer_conds = v.distance_under_km.is_some() || v.s_wave_within_secs.is_some() ||
+		v.estimated_intensity_at_site_over.is_some();
+	let selected = observers.iter().filter(|o| v.observers.as_ref().is_none_or(|n| n.contains(&o.name))).count();
+
+	if has_observer_conds && selected == 0 {
+		warn_never_matches(report, key, negated, "no observer for the observer conditions");
+	}
+}
+
+// `negated` is whether `cond` is under an odd number of `not`
+fn check_condition(report: &mut CheckReport, key: &str, cond: &ConditionConfig,
+	area_dict: &HashMap<[u8; 3], String>, observers: &[Observer], negated: bool)
+{
+	match *cond {
+		ConditionConfig::All { ref all } => {
+			for (i, c) in all.iter().enumerate() {
+				check_condition(report, &format!("{}.all[{}]", key, i), c, area_dict, observers, negated);
+			}
+		},
+		ConditionConfig::Any { ref any } => {
+			if any.is_empty() {
+				warn_never_matches(report, &format!("{}.any", key), negated, "empty list");
+			}
+			for (i, c) in any.iter().enumerate() {
+				check_condition(report, &format!("{}.any[{}]", key, i), c, area_dict, observers, negated);
+			}
+		},
+		ConditionConfig::Not { ref not } => {
+			check_condition(report, &format!("{}.not", key), not, area_dict, observers, ! negated);
+		},
+		ConditionConfig::Value(ref v) => {
+			for reason in contradictions(v) {
+				warn_never_matches(report, key, negated, reason);
+			}
+			for area in v.areas.iter().flat_map(|a| a.iter()) {
+				if ! area_dict.iter().any(|(code, name)| code[..] == *area.as_bytes() || name == area) {
+					report.warn(format!("{}.areas: unknown area `{}`", key, area));
+				}
+			}
+			check_observers(report, key, v, observers, negated);
+		},
+	}
+}
+
+fn check_conditions(report: &mut CheckReport, key: &str, conds: &Option<Vec<ConditionConfig>>,
+	area_dict: &HashMap<[u8; 3], String>, observers: &[Observer])
+{
+	if let Some(ref v) = *conds {
+		if v.is_empty() {
+			report.warn(format!("{}.cond: never matches (empty list)", key));
+		}
+		for (i, c) in v.iter().enumerate() {
+			check_condition(report, &format!("{}.cond[{}]", key, i), c, area_dict, observers, false);
+		}
+	}
+}
+
+pub fn check_config(path: &str) -> CheckReport
+{
+	let mut report = CheckReport::default();
+
+	let conf = match Config::load_config(path) {
+		Ok(c) => c,
+		Err(err) => {
+			report.error(format!("{:?}", err));
+			return report;
+		}
+	};
+
+	let area_dict = &conf.area_dict;
+	let observers = &conf.observers;
+
+	for (i, o) in observers.iter().enumerate() {
+		if ! (-90.0..=90.0).contains(&o.lat) || ! (-180.0..=180.0).contains(&o.lon) {
+			report.error(format!("observers[{}]: invalid position ({}, {})", i, o.lat, o.lon));
+		}
+		if o.amplification.is_nan() || o.amplification <= 0.0 {
+			report.error(format!("observers[{}]: amplification must be positive", i));
+		}
+		if observers[..i].iter().any(|p| p.name == o.name) {
+			report.error(format!("observers[{}]: duplicated name `{}`", i, o.name));
+		}
+	}
+
+	if let Some(ref url) = conf.wni.server_list_url {
+		check_url(&mut report, "wni.server_list_url", url, true);
+	}
+
+	if let Some(ref t) = conf.twitter {
+		let tw = Twitter::new(t.consumer_token.clone(), t.consumer_secret.clone(),
+			t.access_token.clone(), t.access_secret.clone(), t.in_reply_to_enabled, t.updown_enabled,
+			Translator::default());
+		if ! tw.is_valid() {
+			report.error("twitter: invalid token format".to_owned());
+		}
+		check_language(&mut report, "twitter", t.language, &conf.en_names);
+		check_conditions(&mut report, "twitter", &t.cond, area_dict, observers);
+	}
+
+	if let Some(ref s) = conf.slack {
+		match (s.webhook_url.as_ref(), s.bot_token.as_ref(), s.channel.as_ref()) {
+			(Some(url), None, None) =>
+				check_url(&mut report, "slack.webhook_url", url, Slack::build(url, s.updown_enabled, Translator::default()).is_ok()),
+			(None, Some(token), Some(channel)) => {
+				if ! Slack::with_bot_token(token.clone(), channel.clone(), s.updown_enabled,
+					Translator::default()).is_valid() {
+					report.error("slack: invalid bot token or channel".to_owned());
+				}
+			},
+			_ => report.error("slack: either webhook_url or bot_token with channel must be specified".to_owned()),
+		}
+		check_language(&mut report, "slack", s.language, &conf.en_names);
+		check_conditions(&mut report, "slack", &s.cond, area_dict, observers);
+	}
+
+	if let Some(ref p) = conf.pushbullet {
+		let pb = PushBullet::new(p.access_token.clone(), p.device_iden.clone(), p.channel_tag.clone(),
+			p.updown_enabled, Translator::default());
+		if ! pb.is_valid() {
+			report.error("pushbullet: invalid access token".to_owned());
+		}
+		check_language(&mut report, "pushbullet", p.language, &conf.en_names);
+		check_conditions(&mut report, "pushbullet", &p.cond, area_dict, observers);
+	}
+
+	if let Some(ref d) = conf.discord {
+		let valid = Discord::build(&d.webhook_url, d.updown_enabled, Translator::default()).is_ok();
+		check_url(&mut report, "discord.webhook_url", &d.webhook_url, valid);
+		check_language(&mut report, "discord", d.language, &conf.en_names);
+		check_conditions(&mut report, "discord", &d.cond, area_dict, observers);
+	}
+
+	if let Some(ref m) = conf.mqtt {
+		let mq = Mqtt::new(m.host.clone(), m.port, m.client_id.clone(), None,
+			m.topic_prefix.clone(), m.qos, m.retain);
+		if ! mq.is_valid() {
+			report.error("mqtt: invalid client id, topic prefix or qos".to_owned());
+		}
+		if m.password.is_some() && m.username.is_none() {
+			report.warn("mqtt: password is ignored without username".to_owned());
+		}
+		check_conditions(&mut report, "mqtt", &m.cond, area_dict, observers);
+	}
+
+	if let Some(ref s) = conf.stream {
+		check_listen_addr(&mut report, "stream.listen", &s.listen);
+		check_conditions(&mut report, "stream", &s.cond, area_dict, observers);
+	}
+
+	if let Some(ref w) = conf.webhook {
+		let valid = Webhook::build(&w.url, &w.headers, Duration::from_secs(w.timeout_secs), w.retry_count).is_ok();
+		if ! valid {
+			report.error("webhook: invalid url or headers".to_owned());
+		} else {
+			check_url(&mut report, "webhook.url", &w.url, true);
+		}
+		check_conditions(&mut report, "webhook", &w.cond, area_dict, observers);
+	}
+
+	for (i, e) in conf.exec.iter().flat_map(|v| v.iter()).enumerate() {
+		check_conditions(&mut report, &format!("exec[{}]", i), &e.cond, area_dict, observers);
+	}
+
+	if let Some(ref h) = conf.http {
+		check_listen_addr(&mut report, "http.listen", &h.listen);
+	}
+
+	report
+}
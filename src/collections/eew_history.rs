@@ -2,17 +2,19 @@ use std::sync::Arc;
 
 use collections::IndexedLimitedQueue;
 use eew::EEW;
+use metrics::Metrics;
 
 #[derive(Debug, Clone)]
 pub struct EEWHistory {
 	q: IndexedLimitedQueue<Vec<Arc<EEW>>>,
+	latest: Option<Arc<EEW>>,
 }
 
 impl EEWHistory {
 
 	pub fn new(limit: usize) -> EEWHistory
 	{
-		EEWHistory { q: IndexedLimitedQueue::new(limit) }
+		EEWHistory { q: IndexedLimitedQueue::new(limit), latest: None }
 	}
 
 	fn is_acceptable(&self, eew: &EEW) -> bool
@@ -32,9 +34,34 @@ impl EEWHistory {
 			let v = self.q.get_mut_default(eew.id.as_ref());
 			let arc = Arc::new(eew);
 			v.push(arc.clone());
+			self.latest = Some(arc.clone());
+			Metrics::inc_counter("tina_eews_total", &[("result", "accepted")]);
 			Some(arc)
 		} else {
+			Metrics::inc_counter("tina_eews_total", &[("result", "deduplicated")]);
 			None
 		}
 	}
+
+	// appends an EEW restored from the journal; unlike `append`, it is not counted in the metrics
+	pub fn restore(&mut self, eew: EEW)
+	{
+		if self.is_acceptable(&eew) {
+			let arc = Arc::new(eew);
+			self.q.get_mut_default(arc.id.as_ref()).push(arc.clone());
+			self.latest = Some(arc);
+		}
+	}
+
+	// all the accepted reports of the event `id` in order
+	pub fn get(&self, id: &str) -> Option<&[Arc<EEW>]>
+	{
+		self.q.get(id).map(|v| v.as_slice())
+	}
+
+	// the most recently accepted report
+	pub fn latest(&self) -> Option<&Arc<EEW>>
+	{
+		self.latest.as_ref()
+	}
 }
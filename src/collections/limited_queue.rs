@@ -79,6 +79,11 @@ impl<D> IndexedLimitedQueue<D> {
 		}
 	}
 
+	pub fn values(&self) -> impl Iterator<Item = &D> + '_
+	{
+		self.buffer.iter().map(|e| &e.1)
+	}
+
 	pub fn upsert<I>(&mut self, idx: I, mut data: D) -> Option<D>
 		where I: PartialEq<String> + Into<String>
 	{
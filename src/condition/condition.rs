@@ -3,3 +3,11 @@ use eew::EEW;
 pub trait Condition {
 	fn is_satisfied(&self, latest: &EEW, prev: Option<&EEW>) -> bool;
 }
+
+impl<C> Condition for Box<C> where C: Condition + ?Sized {
+
+	fn is_satisfied(&self, latest: &EEW, prev: Option<&EEW>) -> bool
+	{
+		(**self).is_satisfied(latest, prev)
+	}
+}
@@ -1,9 +1,11 @@
 mod condition;
 mod constant_condition;
 mod set_condition;
+mod negated_condition;
 mod value_condition;
 
 pub use self::condition::Condition;
 pub use self::constant_condition::{ConstantCondition, TRUE_CONDITION, FALSE_CONDITION};
-pub use self::set_condition::DisjunctiveCondition;
+pub use self::set_condition::{DisjunctiveCondition, ConjunctiveCondition};
+pub use self::negated_condition::NegatedCondition;
 pub use self::value_condition::ValueCondition;
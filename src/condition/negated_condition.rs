@@ -0,0 +1,12 @@
+use eew::EEW;
+use condition::Condition;
+
+pub struct NegatedCondition<C>(pub C);
+
+impl<C> Condition for NegatedCondition<C> where C: Condition {
+
+	fn is_satisfied(&self, latest: &EEW, prev: Option<&EEW>) -> bool
+	{
+		! self.0.is_satisfied(latest, prev)
+	}
+}
@@ -5,6 +5,10 @@ pub struct DisjunctiveCondition<C> {
 	clauses: Vec<C>,
 }
 
+pub struct ConjunctiveCondition<C> {
+	clauses: Vec<C>,
+}
+
 impl<C> DisjunctiveCondition<C> {
 
 	fn new(clauses: Vec<C>) -> DisjunctiveCondition<C>
@@ -28,3 +32,27 @@ impl<C> From<Vec<C>> for DisjunctiveCondition<C> {
 		DisjunctiveCondition::new(clauses)
 	}
 }
+
+impl<C> ConjunctiveCondition<C> {
+
+	fn new(clauses: Vec<C>) -> ConjunctiveCondition<C>
+	{
+		ConjunctiveCondition { clauses: clauses }
+	}
+}
+
+impl<C> Condition for ConjunctiveCondition<C> where C: Condition {
+
+	fn is_satisfied(&self, latest: &EEW, prev: Option<&EEW>) -> bool
+	{
+		self.clauses.iter().all(|c| c.is_satisfied(latest, prev))
+	}
+}
+
+impl<C> From<Vec<C>> for ConjunctiveCondition<C> {
+
+	fn from(clauses: Vec<C>) -> ConjunctiveCondition<C>
+	{
+		ConjunctiveCondition::new(clauses)
+	}
+}
@@ -1,7 +1,9 @@
-use eew::{EEW, EEWPhase, EEWDetail, IntensityClass};
+use eew::{EEW, EEWPhase, EEWDetail, IntensityClass, WarningStatus, WaveStatus};
 use condition::Condition;
+use observer::Observer;
 
 
+#[derive(Default)]
 pub struct ValueCondition {
 
 	pub first: Option<bool>,
@@ -21,6 +23,16 @@ pub struct ValueCondition {
 
 	pub intensity_up: Option<u8>,
 	pub intensity_down: Option<u8>,
+
+	pub areas: Option<Vec<String>>,
+	pub area_intensity_over: Option<IntensityClass>,
+	pub area_alert: Option<bool>,
+	pub area_wave_status: Option<WaveStatus>,
+
+	pub observers: Vec<Observer>,
+	pub distance_under_km: Option<f32>,
+	pub s_wave_within_secs: Option<f32>,
+	pub estimated_intensity_at_site_over: Option<IntensityClass>,
 }
 
 fn test_bool(expected: Option<bool>, actual: bool) -> bool
@@ -61,6 +73,58 @@ fn test_with_prev_detail<V, F>(expected: Option<V>, latest: &EEW, prev: Option<&
 	}
 }
 
+impl ValueCondition {
+
+	fn has_area_conditions(&self) -> bool
+	{
+		self.areas.is_some() || self.area_intensity_over.is_some() ||
+			self.area_alert.is_some() || self.area_wave_status.is_some()
+	}
+
+	// satisfied when at least one of the specified areas meets all the area conditions
+	fn test_areas(&self, latest: &EEW) -> bool
+	{
+		if ! self.has_area_conditions() {
+			return true;
+		}
+
+		let detail = match latest.detail {
+			Some(ref d) => d,
+			None => return false,
+		};
+
+		detail.area_info.iter().any(|area| {
+			let intensity = area.maximum_intensity.unwrap_or(area.minimum_intensity);
+			self.areas.as_ref().map_or(true, |names| names.iter().any(|n| *n == area.area_code || *n == area.area_name)) &&
+				self.area_intensity_over.map_or(true, |v| intensity >= v) &&
+				test_bool(self.area_alert, area.warning_status == WarningStatus::Alert) &&
+				self.area_wave_status.map_or(true, |v| area.wave_status == v)
+		})
+	}
+
+	fn has_observer_conditions(&self) -> bool
+	{
+		self.distance_under_km.is_some() || self.s_wave_within_secs.is_some() ||
+			self.estimated_intensity_at_site_over.is_some()
+	}
+
+	// satisfied when at least one of the observers meets all the observer conditions;
+	// the S-wave arrival is measured from the issue of the EEW
+	fn test_observers(&self, latest: &EEW) -> bool
+	{
+		if ! self.has_observer_conditions() {
+			return true;
+		}
+
+		self.observers.iter().filter_map(|o| o.arrival(latest).map(|a| (o, a))).any(|(observer, arrival)| {
+			self.distance_under_km.map_or(true, |v| arrival.distance_km <= v) &&
+				self.s_wave_within_secs.map_or(true, |v| arrival.s_wave_secs_from(latest.issued_at) <= v) &&
+				self.estimated_intensity_at_site_over.map_or(true,
+					|v| observer.estimated_intensity(latest).map_or(false, |i| i >= v))
+		})
+	}
+}
+
 impl Condition for ValueCondition {
 
 	fn is_satisfied(&self, latest: &EEW, prev: Option<&EEW>) -> bool
@@ -75,6 +139,8 @@ impl Condition for ValueCondition {
 			test_bool(self.test, latest.is_test()),
 			test_detail(self.magnitude_over, latest, |v, detail| detail.magnitude.map_or(false, |m| m >= v)),
 			test_detail(self.intensity_over, latest, |v, detail| detail.maximum_intensity.map_or(false, |m| m >= v)),
+			self.test_areas(latest),
+			self.test_observers(latest),
 		];
 
 		let comp_conds = [
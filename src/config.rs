@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::Read;
-use std::error::Error as StdError;
+use std::sync::Arc;
 
 use csv::ReaderBuilder;
 use serde::{Deserializer, Deserialize};
@@ -10,16 +10,23 @@ use serde_yaml;
 use serde_yaml::Value;
 use slog::Level;
 
-use tina::{ValueCondition, DisjunctiveCondition, IntensityClass};
+use condition::{Condition, ValueCondition, DisjunctiveCondition, ConjunctiveCondition, NegatedCondition};
+use eew::{WaveStatus, IntensityClass};
+use translator::{Language, NameDict, Translator, Template, TemplateError};
+use observer::Observer;
+use travel_time::TravelTimeTable;
 
 
 #[derive(Debug, Clone)]
 pub enum ConfigLoadError {
 	ConfigFileIo,
-	CodeDictFileIO,
-	InvalidCodeFormat,
-	InvalidYamlFormat,
+	CodeDictFileIO(String),
+	InvalidCodeFormat(String),
+	DuplicatedCode(String),
+	InvalidYamlFormat(String),
 	InvalidKeyValue(String),
+	InvalidTemplate(String),
+	InvalidTravelTimeTable(String),
 }
 
 #[derive(Deserialize, Debug)]
@@ -29,7 +36,17 @@ struct RawRootConfig {
 	pub wni: WniConfig,
 	pub twitter: Option<TwitterConfig>,
 	pub slack: Option<SlackConfig>,
+	pub pushbullet: Option<PushBulletConfig>,
+	pub discord: Option<DiscordConfig>,
+	pub mqtt: Option<MqttConfig>,
+	pub stream: Option<StreamConfig>,
+	pub webhook: Option<WebhookConfig>,
+	pub exec: Option<Vec<ExecConfig>>,
+	pub http: Option<HttpConfig>,
+	pub journal: Option<JournalConfig>,
+	#[serde(default)] pub observers: Vec<Observer>,
 	pub log: LogConfig,
+	#[serde(default)] pub auto_reload: bool,
 }
 
 #[derive(Deserialize, Debug)]
@@ -37,6 +54,10 @@ struct RawRootConfig {
 struct DictPathConfig {
 	pub area: String,
 	pub epicenter: String,
+	pub area_en: Option<String>,
+	pub epicenter_en: Option<String>,
+	// the JMA2001 travel time table (`tjma2001`) used to estimate the arrival at the observers
+	pub travel_time: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -49,11 +70,25 @@ pub struct LogConfig {
 	#[serde(deserialize_with = "deserialize_log_level")] pub log_level: Level,
 }
 
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct HttpConfig {
+	pub listen: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct JournalConfig {
+	pub path: String,
+	#[serde(default="def_journal_retention_secs")] pub retention_secs: u64,
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct WniConfig {
 	pub id: String,
 	pub password: String,
+	pub server_list_url: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -65,15 +100,94 @@ pub struct TwitterConfig {
 	pub access_secret: String,
 	#[serde(default)] pub in_reply_to_enabled: bool,
 	#[serde(default)] pub updown_enabled: bool,
-	pub cond: Option<Vec<ValueConditionConfig>>,
+	#[serde(default)] pub language: Language,
+	pub template: Option<String>,
+	#[serde(default, deserialize_with="deserialize_conditions")] pub cond: Option<Vec<ConditionConfig>>,
 }
 
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct SlackConfig {
+	pub webhook_url: Option<String>,
+	pub bot_token: Option<String>,
+	pub channel: Option<String>,
+	#[serde(default)] pub updown_enabled: bool,
+	#[serde(default)] pub language: Language,
+	pub template: Option<String>,
+	#[serde(default, deserialize_with="deserialize_conditions")] pub cond: Option<Vec<ConditionConfig>>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PushBulletConfig {
+	pub access_token: String,
+	pub device_iden: Option<String>,
+	pub channel_tag: Option<String>,
+	#[serde(default)] pub updown_enabled: bool,
+	#[serde(default)] pub language: Language,
+	pub template: Option<String>,
+	#[serde(default, deserialize_with="deserialize_conditions")] pub cond: Option<Vec<ConditionConfig>>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct DiscordConfig {
 	pub webhook_url: String,
 	#[serde(default)] pub updown_enabled: bool,
-	pub cond: Option<Vec<ValueConditionConfig>>,
+	#[serde(default)] pub language: Language,
+	pub template: Option<String>,
+	#[serde(default, deserialize_with="deserialize_conditions")] pub cond: Option<Vec<ConditionConfig>>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct MqttConfig {
+	pub host: String,
+	#[serde(default="def_mqtt_port")] pub port: u16,
+	#[serde(default="def_mqtt_client_id")] pub client_id: String,
+	pub username: Option<String>,
+	pub password: Option<String>,
+	#[serde(default="def_mqtt_topic_prefix")] pub topic_prefix: String,
+	#[serde(default)] pub qos: u8,
+	#[serde(default)] pub retain: bool,
+	#[serde(default, deserialize_with="deserialize_conditions")] pub cond: Option<Vec<ConditionConfig>>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct StreamConfig {
+	pub listen: String,
+	#[serde(default="def_stream_active_secs")] pub active_secs: u64,
+	#[serde(default, deserialize_with="deserialize_conditions")] pub cond: Option<Vec<ConditionConfig>>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+	pub url: String,
+	#[serde(default)] pub headers: HashMap<String, String>,
+	#[serde(default="def_webhook_timeout_secs")] pub timeout_secs: u64,
+	#[serde(default="def_webhook_retry_count")] pub retry_count: u32,
+	#[serde(default, deserialize_with="deserialize_conditions")] pub cond: Option<Vec<ConditionConfig>>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ExecConfig {
+	pub program: String,
+	#[serde(default)] pub args: Vec<String>,
+	#[serde(default="def_exec_timeout_secs")] pub timeout_secs: u64,
+	#[serde(default="def_exec_max_concurrency")] pub max_concurrency: usize,
+	#[serde(default, deserialize_with="deserialize_conditions")] pub cond: Option<Vec<ConditionConfig>>,
+}
+
+// `all`, `any` and `not` can be nested; any other mapping is a ValueConditionConfig
+#[derive(Debug, Clone)]
+pub enum ConditionConfig {
+	All { all: Vec<ConditionConfig> },
+	Any { any: Vec<ConditionConfig> },
+	Not { not: Box<ConditionConfig> },
+	Value(ValueConditionConfig),
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -86,8 +200,9 @@ pub struct ValueConditionConfig {
 	pub alert: Option<bool>,
 	pub last: Option<bool>,
 	pub cancel: Option<bool>,
-	#[serde(default="def_opt_false")] pub drill: Option<bool>,
-	#[serde(default="def_opt_false")] pub test: Option<bool>,
+	// drills and tests are rejected unless these are given (see `build_yaml_condition`)
+	pub drill: Option<bool>,
+	pub test: Option<bool>,
 
 	pub phase_changed: Option<bool>,
 	pub epicenter_name_changed: Option<bool>,
@@ -97,16 +212,101 @@ pub struct ValueConditionConfig {
 
 	pub intensity_up: Option<u8>,
 	pub intensity_down: Option<u8>,
+
+	pub areas: Option<Vec<String>>,
+	pub area_intensity_over: Option<f32>,
+	pub area_alert: Option<bool>,
+	pub area_wave_status: Option<WaveStatus>,
+
+	pub observers: Option<Vec<String>>,
+	pub distance_under_km: Option<f32>,
+	pub s_wave_within_secs: Option<f32>,
+	pub estimated_intensity_at_site_over: Option<f32>,
 }
 
 #[derive(Debug)]
 pub struct Config {
 	pub area_dict: HashMap<[u8; 3], String>,
 	pub epicenter_dict: HashMap<[u8; 3], String>,
+	pub en_names: Arc<NameDict>,
 	pub wni: WniConfig,
 	pub twitter: Option<TwitterConfig>,
 	pub slack: Option<SlackConfig>,
+	pub pushbullet: Option<PushBulletConfig>,
+	pub discord: Option<DiscordConfig>,
+	pub mqtt: Option<MqttConfig>,
+	pub stream: Option<StreamConfig>,
+	pub webhook: Option<WebhookConfig>,
+	pub exec: Option<Vec<ExecConfig>>,
+	pub http: Option<HttpConfig>,
+	pub journal: Option<JournalConfig>,
+	pub observers: Vec<Observer>,
 	pub log: LogConfig,
+	pub auto_reload: bool,
+}
+
+fn with_key_path(path: &str, msg: String) -> String
+{
+	match path.is_empty() {
+		true => msg,
+		false => format!("{}: {}", path, msg),
+	}
+}
+
+fn parse_condition_list(value: Value, path: &str) -> Result<Vec<ConditionConfig>, String>
+{
+	match value {
+		Value::Sequence(seq) => seq.into_iter().enumerate()
+			.map(|(i, v)| parse_condition(v, &format!("{}[{}]", path, i)))
+			.collect(),
+		_ => Err(with_key_path(path, "expected a list of conditions".to_owned())),
+	}
+}
+
+// errors are prefixed with the key path of the invalid condition (e.g. `cond[2].all[1].not`)
+// since serde cannot tell which variant of nested conditions is invalid
+fn parse_condition(value: Value, path: &str) -> Result<ConditionConfig, String>
+{
+	let combinator = match value {
+		Value::Mapping(ref m) if m.len() == 1 => m.iter().next()
+			.and_then(|(k, v)| k.as_str().map(|k| (k.to_owned(), v.clone())))
+			.filter(|(k, _)| k == "all" || k == "any" || k == "not"),
+		_ => None,
+	};
+
+	match combinator {
+		Some((key, v)) => {
+			let path = match path.is_empty() {
+				true => key.clone(),
+				false => format!("{}.{}", path, key),
+			};
+			match key.as_str() {
+				"all" => Ok(ConditionConfig::All { all: parse_condition_list(v, &path)? }),
+				"any" => Ok(ConditionConfig::Any { any: parse_condition_list(v, &path)? }),
+				_ => Ok(ConditionConfig::Not { not: Box::new(parse_condition(v, &path)?) }),
+			}
+		},
+		None => serde_yaml::from_value(value).map(ConditionConfig::Value).map_err(|e| with_key_path(path, e.to_string())),
+	}
+}
+
+impl<'d> Deserialize<'d> for ConditionConfig {
+
+	fn deserialize<D>(deserializer: D) -> Result<ConditionConfig, D::Error> where D: Deserializer<'d>
+	{
+		let value = Value::deserialize(deserializer)?;
+		parse_condition(value, "").map_err(D::Error::custom)
+	}
+}
+
+// `cond` of destinations; errors have the key path from `cond` (e.g. `cond[2].all[1]`)
+fn deserialize_conditions<'d, D>(deserializer: D) -> Result<Option<Vec<ConditionConfig>>, D::Error>
+	where D: Deserializer<'d>
+{
+	match Option::<Value>::deserialize(deserializer)? {
+		Some(value) => parse_condition_list(value, "cond").map(Some).map_err(D::Error::custom),
+		None => Ok(None),
+	}
 }
 
 impl From<ValueConditionConfig> for ValueCondition {
@@ -120,13 +320,67 @@ impl From<ValueConditionConfig> for ValueCondition {
 			magnitude_over: conf.magnitude_over,
 			intensity_over: conf.intensity_over.map(|i| IntensityClass::new(i)),
 			intensity_up: conf.intensity_up, intensity_down: conf.intensity_down,
+			areas: conf.areas,
+			area_intensity_over: conf.area_intensity_over.map(|i| IntensityClass::new(i)),
+			area_alert: conf.area_alert, area_wave_status: conf.area_wave_status,
+			observers: Vec::new(),
+			distance_under_km: conf.distance_under_km, s_wave_within_secs: conf.s_wave_within_secs,
+			estimated_intensity_at_site_over: conf.estimated_intensity_at_site_over.map(|i| IntensityClass::new(i)),
 		}
 	}
 }
 
-pub fn build_yaml_condition(v: Vec<ValueConditionConfig>) -> DisjunctiveCondition<ValueCondition>
+// whether any value condition in `conf` satisfies `f`
+fn any_value<F>(conf: &ConditionConfig, f: &F) -> bool where F: Fn(&ValueConditionConfig) -> bool
+{
+	match *conf {
+		ConditionConfig::All { ref all } => all.iter().any(|c| any_value(c, f)),
+		ConditionConfig::Any { ref any } => any.iter().any(|c| any_value(c, f)),
+		ConditionConfig::Not { ref not } => any_value(not, f),
+		ConditionConfig::Value(ref v) => f(v),
+	}
+}
+
+// the observer conditions are tested against the observers named in `observers` (all of them if omitted);
+// omitted `drill` and `test` reject drills and tests except under an odd number of `not`,
+// where the negation would accept them instead
+fn build_condition(conf: ConditionConfig, observers: &[Observer], negated: bool) -> Box<dyn Condition>
 {
-	v.into_iter().map(|vc| vc.into()).collect::<Vec<ValueCondition>>().into()
+	let build = |c| build_condition(c, observers, negated);
+
+	match conf {
+		ConditionConfig::All { all } =>
+			Box::new(ConjunctiveCondition::from(all.into_iter().map(build).collect::<Vec<_>>())),
+		ConditionConfig::Any { any } =>
+			Box::new(DisjunctiveCondition::from(any.into_iter().map(build).collect::<Vec<_>>())),
+		ConditionConfig::Not { not } =>
+			Box::new(NegatedCondition(build_condition(*not, observers, ! negated))),
+		ConditionConfig::Value(mut vc) => {
+			if ! negated {
+				vc.drill = vc.drill.or(Some(false));
+				vc.test = vc.test.or(Some(false));
+			}
+			let selected = observers.iter()
+				.filter(|o| vc.observers.as_ref().is_none_or(|names| names.contains(&o.name)))
+				.cloned().collect();
+			Box::new(ValueCondition { observers: selected, .. ValueCondition::from(vc) })
+		},
+	}
+}
+
+// each clause rejects drills and tests unless `drill` or `test` is given somewhere in it
+// (e.g. `not: { alert: true }` still rejects drills and tests)
+pub fn build_yaml_condition(v: Vec<ConditionConfig>, observers: &[Observer]) -> DisjunctiveCondition<Box<dyn Condition>>
+{
+	v.into_iter().map(|c| {
+		let default = ValueCondition {
+			drill: if any_value(&c, &|v| v.drill.is_some()) { None } else { Some(false) },
+			test: if any_value(&c, &|v| v.test.is_some()) { None } else { Some(false) },
+			.. ValueCondition::default()
+		};
+		let cond = build_condition(c, observers, false);
+		Box::new(ConjunctiveCondition::from(vec!{cond, Box::new(default) as Box<dyn Condition>})) as Box<dyn Condition>
+	}).collect::<Vec<_>>().into()
 }
 
 fn deserialize_log_level<'d, D>(deserializer: D) -> Result<Level, D::Error>
@@ -146,28 +400,45 @@ fn deserialize_log_level<'d, D>(deserializer: D) -> Result<Level, D::Error>
 	}
 }
 
-fn def_opt_false() -> Option<bool> { Some(false) }
+fn def_webhook_timeout_secs() -> u64 { 10 }
+fn def_webhook_retry_count() -> u32 { 2 }
+fn def_exec_timeout_secs() -> u64 { 60 }
+fn def_exec_max_concurrency() -> usize { 1 }
+fn def_mqtt_port() -> u16 { 1883 }
+fn def_mqtt_client_id() -> String { "tina".to_owned() }
+fn def_mqtt_topic_prefix() -> String { "tina".to_owned() }
+fn def_stream_active_secs() -> u64 { 300 }
+fn def_journal_retention_secs() -> u64 { 3600 }
 
 
+// errors contain the path and the line number of the invalid record
 fn load_code_dict(path: &str) -> Result<HashMap<[u8; 3], String>, ConfigLoadError>
 {
-	let mut reader = try!(ReaderBuilder::new().has_headers(false).from_path(path).map_err(|_| ConfigLoadError::CodeDictFileIO));
+	let mut reader = try!(ReaderBuilder::new().has_headers(false).from_path(path)
+		.map_err(|_| ConfigLoadError::CodeDictFileIO(path.to_owned())));
 
 	let mut dict = HashMap::new();
 
-	for record in reader.deserialize() {
+	for record in reader.records() {
+
+		let record = try!(record.map_err(|e| ConfigLoadError::InvalidCodeFormat(format!("{}: {}", path, e))));
+		let line = record.position().map_or(0, |p| p.line());
 
-		let (code, name): (String, String) = try!(record.map_err(|_| ConfigLoadError::InvalidCodeFormat));
+		let (code, name): (String, String) = try!(record.deserialize(None)
+			.map_err(|_| ConfigLoadError::InvalidCodeFormat(format!("{}:{}: expected `code,name`", path, line))));
 
 		let mut encoded = [0; 3];
 		let bytes = code.as_bytes();
 
-		if bytes.len() != encoded.len() {
-			return Err(ConfigLoadError::InvalidCodeFormat);
+		if bytes.len() != encoded.len() || ! bytes.iter().all(u8::is_ascii_digit) {
+			return Err(ConfigLoadError::InvalidCodeFormat(format!("{}:{}: invalid code `{}`", path, line, code)));
 		}
 
 		encoded.copy_from_slice(bytes);
-		dict.insert(encoded, name);
+
+		if dict.insert(encoded, name).is_some() {
+			return Err(ConfigLoadError::DuplicatedCode(format!("{}:{}: duplicated code `{}`", path, line, code)));
+		}
 	}
 
 	return Ok(dict);
@@ -181,24 +452,85 @@ impl Config {
 		let mut data = String::new();
 		try!(file.read_to_string(&mut data).map_err(|_| ConfigLoadError::ConfigFileIo));
 
-		let raw_value: Value =
-			try!(serde_yaml::from_str(&data).map_err(|_| ConfigLoadError::InvalidYamlFormat));
+		// deserializing from the string (not from a Value) keeps line and column numbers in errors
+		let _: Value =
+			try!(serde_yaml::from_str(&data).map_err(|err| ConfigLoadError::InvalidYamlFormat(err.to_string())));
 		let raw_root_conf: RawRootConfig =
-			serde_yaml::from_value(raw_value.clone())
-			.map_err(|err| ConfigLoadError::InvalidKeyValue(err.description().to_owned()))?;
+			serde_yaml::from_str(&data)
+			.map_err(|err| ConfigLoadError::InvalidKeyValue(err.to_string()))?;
 
 		let area_dict = try!(load_code_dict(&raw_root_conf.path.area));
 		let epicenter_dict = try!(load_code_dict(&raw_root_conf.path.epicenter));
 
+		let mut en_names = NameDict::default();
+		if let Some(ref path) = raw_root_conf.path.area_en {
+			en_names.area = try!(load_code_dict(path));
+		}
+		if let Some(ref path) = raw_root_conf.path.epicenter_en {
+			en_names.epicenter = try!(load_code_dict(path));
+		}
+
+		let mut observers = raw_root_conf.observers;
+		if let Some(ref path) = raw_root_conf.path.travel_time {
+			let table = Arc::new(try!(TravelTimeTable::load(path).map_err(ConfigLoadError::InvalidTravelTimeTable)));
+			for o in observers.iter_mut() {
+				o.travel_times = Some(table.clone());
+			}
+		}
+
+		let templates = [
+			("twitter", raw_root_conf.twitter.as_ref().map(|c| (&c.template, c.language))),
+			("slack", raw_root_conf.slack.as_ref().map(|c| (&c.template, c.language))),
+			("pushbullet", raw_root_conf.pushbullet.as_ref().map(|c| (&c.template, c.language))),
+			("discord", raw_root_conf.discord.as_ref().map(|c| (&c.template, c.language))),
+		];
+
+		for &(key, t) in templates.iter() {
+			if let Some((Some(source), language)) = t {
+				if let Err(TemplateError::InvalidSyntax(msg)) = Template::new(source, language) {
+					return Err(ConfigLoadError::InvalidTemplate(format!("{}.template: {}", key, msg)));
+				}
+			}
+		}
+
+		// `Exec` skips every EEW without a free slot
+		for (i, e) in raw_root_conf.exec.iter().flat_map(|v| v.iter()).enumerate() {
+			if e.max_concurrency == 0 {
+				return Err(ConfigLoadError::InvalidKeyValue(format!("exec[{}].max_concurrency: must be positive", i)));
+			}
+		}
+
 		let conf = Config {
 			area_dict: area_dict,
 			epicenter_dict: epicenter_dict,
+			en_names: Arc::new(en_names),
 			wni: raw_root_conf.wni,
 			twitter: raw_root_conf.twitter,
 			slack: raw_root_conf.slack,
+			pushbullet: raw_root_conf.pushbullet,
+			discord: raw_root_conf.discord,
+			mqtt: raw_root_conf.mqtt,
+			stream: raw_root_conf.stream,
+			webhook: raw_root_conf.webhook,
+			exec: raw_root_conf.exec,
+			http: raw_root_conf.http,
+			journal: raw_root_conf.journal,
+			observers: observers,
 			log: raw_root_conf.log,
+			auto_reload: raw_root_conf.auto_reload,
 		};
 
 		Ok(conf)
 	}
+
+	// templates are validated in `load_config`
+	pub fn translator(&self, language: Language, template: &Option<String>) -> Translator
+	{
+		let translator = Translator::new(language, self.en_names.clone()).with_observers(self.observers.clone());
+
+		match *template {
+			Some(ref source) => translator.with_template(source).expect("invalid template"),
+			None => translator,
+		}
+	}
 }
@@ -0,0 +1,124 @@
+use reqwest::{Client, Url, StatusCode};
+use serde_json::Value;
+
+pub struct DiscordClient {
+	webhook_url: Url,
+	client: Client,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum DiscordError {
+	Network,
+	NotFound,
+	RateLimitExceeded,
+	InvalidResponse,
+	Rejected(u16),
+}
+
+impl DiscordError {
+
+	pub fn check_status(status: StatusCode) -> Result<(), DiscordError>
+	{
+		match status {
+			s if s.is_success() => Ok(()),
+			StatusCode::NOT_FOUND => Err(DiscordError::NotFound),
+			StatusCode::TOO_MANY_REQUESTS => Err(DiscordError::RateLimitExceeded),
+			s => Err(DiscordError::Rejected(s.as_u16())),
+		}
+	}
+
+	// the message has been deleted on the Discord side
+	pub fn is_message_lost(&self) -> bool
+	{
+		*self == DiscordError::NotFound
+	}
+
+	pub fn parse_message_id(message: &Value) -> Result<String, DiscordError>
+	{
+		match message["id"].as_str() {
+			Some(id) => Ok(id.to_owned()),
+			None => Err(DiscordError::InvalidResponse),
+		}
+	}
+}
+
+impl DiscordClient {
+
+	pub fn build(webhook_url: &str) -> Result<DiscordClient, ()>
+	{
+		let webhook_url = Url::parse(webhook_url).map_err(|_| ())?;
+
+		if webhook_url.cannot_be_a_base() {
+			return Err(());
+		}
+
+		Ok(DiscordClient {
+			webhook_url: webhook_url,
+			client: Client::new(),
+		})
+	}
+
+	// returns the ID of the posted message
+	pub fn post_embed(&self, embed: &Value) -> Result<String, DiscordError>
+	{
+		let mut url = self.webhook_url.clone();
+		// without `wait`, Discord returns no content and thus no message ID
+		url.query_pairs_mut().append_pair("wait", "true");
+
+		let mut response = self.client.post(url)
+			.json(&json!({ "embeds": [embed] }))
+			.send()
+			.map_err(|_| DiscordError::Network)?;
+
+		DiscordError::check_status(response.status())?;
+
+		let message: Value = response.json().map_err(|_| DiscordError::InvalidResponse)?;
+		DiscordError::parse_message_id(&message)
+	}
+
+	pub fn edit_embed(&self, message_id: &str, embed: &Value) -> Result<(), DiscordError>
+	{
+		let mut url = self.webhook_url.clone();
+		url.path_segments_mut().map_err(|_| DiscordError::InvalidResponse)?
+			.pop_if_empty().push("messages").push(message_id);
+
+		let response = self.client.patch(url)
+			.json(&json!({ "embeds": [embed] }))
+			.send()
+			.map_err(|_| DiscordError::Network)?;
+
+		DiscordError::check_status(response.status())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use super::*;
+
+	#[test]
+	fn it_should_map_statuses()
+	{
+		assert_eq!(DiscordError::check_status(StatusCode::OK), Ok(()));
+		assert_eq!(DiscordError::check_status(StatusCode::NO_CONTENT), Ok(()));
+		assert_eq!(DiscordError::check_status(StatusCode::NOT_FOUND), Err(DiscordError::NotFound));
+		assert_eq!(DiscordError::check_status(StatusCode::TOO_MANY_REQUESTS), Err(DiscordError::RateLimitExceeded));
+		assert_eq!(DiscordError::check_status(StatusCode::BAD_REQUEST), Err(DiscordError::Rejected(400)));
+	}
+
+	#[test]
+	fn it_should_repost_only_lost_messages()
+	{
+		assert!(DiscordError::NotFound.is_message_lost());
+		assert!(! DiscordError::RateLimitExceeded.is_message_lost());
+		assert!(! DiscordError::Rejected(403).is_message_lost());
+	}
+
+	#[test]
+	fn it_should_parse_message_ids()
+	{
+		assert_eq!(DiscordError::parse_message_id(&json!({ "id": "1234", "type": 0 })), Ok("1234".to_owned()));
+		assert_eq!(DiscordError::parse_message_id(&json!({ "id": 1234 })), Err(DiscordError::InvalidResponse));
+		assert_eq!(DiscordError::parse_message_id(&json!(null)), Err(DiscordError::InvalidResponse));
+	}
+}
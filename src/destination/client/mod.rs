@@ -1,5 +1,13 @@
 mod twitter_client;
 mod slack_client;
+mod webhook_client;
+mod pushbullet_client;
+mod discord_client;
+mod mqtt_client;
 
 pub use self::twitter_client::TwitterClient;
-pub use self::slack_client::{SlackClient, SlackError, SlackMessageType};
+pub use self::slack_client::{SlackClient, SlackError, SlackMessage, SlackMessageType};
+pub use self::webhook_client::WebhookClient;
+pub use self::pushbullet_client::{PushBulletClient, PushBulletError};
+pub use self::discord_client::DiscordClient;
+pub use self::mqtt_client::{MqttClient, MqttWill};
@@ -0,0 +1,234 @@
+use std::io;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+// a minimal MQTT 3.1.1 client which only publishes messages
+
+const PROTOCOL_LEVEL: u8 = 4;
+
+const CONNECT: u8 = 0x10;
+const CONNACK: u8 = 0x20;
+const PUBLISH: u8 = 0x30;
+const PUBACK: u8 = 0x40;
+const PUBREC: u8 = 0x50;
+const PUBREL: u8 = 0x62;
+const PUBCOMP: u8 = 0x70;
+const PINGREQ: u8 = 0xC0;
+const PINGRESP: u8 = 0xD0;
+const DISCONNECT: u8 = 0xE0;
+
+const MAX_REMAINING_LENGTH: usize = 268_435_455;
+
+pub struct MqttClient {
+	stream: TcpStream,
+	next_packet_id: u16,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum MqttError {
+	Network,
+	Refused(u8),
+	Protocol,
+	TooLarge,
+}
+
+#[derive(Debug, Clone)]
+pub struct MqttWill {
+	pub topic: String,
+	pub payload: Vec<u8>,
+	pub qos: u8,
+	pub retain: bool,
+}
+
+impl From<io::Error> for MqttError {
+	fn from(_: io::Error) -> MqttError { MqttError::Network }
+}
+
+fn put_string(buf: &mut Vec<u8>, s: &[u8])
+{
+	buf.push((s.len() >> 8) as u8);
+	buf.push(s.len() as u8);
+	buf.extend_from_slice(s);
+}
+
+fn encode_packet(header: u8, body: &[u8]) -> Result<Vec<u8>, MqttError>
+{
+	if body.len() > MAX_REMAINING_LENGTH {
+		return Err(MqttError::TooLarge);
+	}
+
+	let mut packet = vec![header];
+	let mut len = body.len();
+
+	loop {
+		let mut b = (len % 128) as u8;
+		len /= 128;
+		if len > 0 {
+			b |= 0x80;
+		}
+		packet.push(b);
+		if len == 0 {
+			break;
+		}
+	}
+
+	packet.extend_from_slice(body);
+	Ok(packet)
+}
+
+fn read_packet(stream: &mut TcpStream) -> Result<(u8, Vec<u8>), MqttError>
+{
+	let mut b = [0; 1];
+	stream.read_exact(&mut b)?;
+	let header = b[0];
+
+	let mut len = 0;
+	let mut multiplier = 1;
+
+	loop {
+		stream.read_exact(&mut b)?;
+		len += (b[0] & 0x7F) as usize * multiplier;
+		if b[0] & 0x80 == 0 {
+			break;
+		}
+		multiplier *= 128;
+		if multiplier > 128 * 128 * 128 {
+			return Err(MqttError::Protocol);
+		}
+	}
+
+	let mut body = vec![0; len];
+	stream.read_exact(&mut body)?;
+
+	Ok((header, body))
+}
+
+fn packet_id(body: &[u8]) -> Option<u16>
+{
+	match body.len() {
+		2 => Some(((body[0] as u16) << 8) | body[1] as u16),
+		_ => None,
+	}
+}
+
+impl MqttClient {
+
+	pub fn connect(addr: &str, client_id: &str, credentials: Option<(&str, &str)>,
+		keep_alive_secs: u16, will: Option<&MqttWill>, timeout: Duration) -> Result<MqttClient, MqttError>
+	{
+		let sock_addr = addr.to_socket_addrs()?.next().ok_or(MqttError::Network)?;
+		let stream = TcpStream::connect_timeout(&sock_addr, timeout)?;
+		stream.set_read_timeout(Some(timeout))?;
+		stream.set_write_timeout(Some(timeout))?;
+		stream.set_nodelay(true)?;
+
+		let mut flags = 0x02; // clean session
+
+		if let Some(w) = will {
+			flags |= 0x04 | (w.qos << 3);
+			if w.retain {
+				flags |= 0x20;
+			}
+		}
+
+		if credentials.is_some() {
+			flags |= 0x80 | 0x40;
+		}
+
+		let mut body = Vec::new();
+		put_string(&mut body, b"MQTT");
+		body.push(PROTOCOL_LEVEL);
+		body.push(flags);
+		body.push((keep_alive_secs >> 8) as u8);
+		body.push(keep_alive_secs as u8);
+		put_string(&mut body, client_id.as_bytes());
+
+		if let Some(w) = will {
+			put_string(&mut body, w.topic.as_bytes());
+			put_string(&mut body, &w.payload);
+		}
+
+		if let Some((username, password)) = credentials {
+			put_string(&mut body, username.as_bytes());
+			put_string(&mut body, password.as_bytes());
+		}
+
+		let mut client = MqttClient { stream: stream, next_packet_id: 1 };
+		client.send(CONNECT, &body)?;
+
+		match read_packet(&mut client.stream)? {
+			(CONNACK, ref body) if body.len() == 2 && body[1] == 0 => Ok(client),
+			(CONNACK, ref body) if body.len() == 2 => Err(MqttError::Refused(body[1])),
+			_ => Err(MqttError::Protocol),
+		}
+	}
+
+	fn send(&mut self, header: u8, body: &[u8]) -> Result<(), MqttError>
+	{
+		let packet = encode_packet(header, body)?;
+		self.stream.write_all(&packet)?;
+		self.stream.flush()?;
+		Ok(())
+	}
+
+	// waits for the packet `expected`, skipping unrelated ones
+	fn wait_for(&mut self, expected: u8, id: Option<u16>) -> Result<(), MqttError>
+	{
+		loop {
+			let (header, body) = read_packet(&mut self.stream)?;
+			if header == expected && (id.is_none() || packet_id(&body) == id) {
+				return Ok(());
+			}
+		}
+	}
+
+	pub fn publish(&mut self, topic: &str, payload: &[u8], qos: u8, retain: bool) -> Result<(), MqttError>
+	{
+		if qos > 2 {
+			return Err(MqttError::Protocol);
+		}
+
+		let mut body = Vec::new();
+		put_string(&mut body, topic.as_bytes());
+
+		let id = self.next_packet_id;
+
+		if qos > 0 {
+			body.push((id >> 8) as u8);
+			body.push(id as u8);
+			self.next_packet_id = match id {
+				u16::MAX => 1,
+				_ => id + 1,
+			};
+		}
+
+		body.extend_from_slice(payload);
+
+		let header = PUBLISH | (qos << 1) | (if retain { 1 } else { 0 });
+		self.send(header, &body)?;
+
+		let id_bytes = [(id >> 8) as u8, id as u8];
+
+		match qos {
+			1 => self.wait_for(PUBACK, Some(id)),
+			2 => {
+				self.wait_for(PUBREC, Some(id))?;
+				self.send(PUBREL, &id_bytes)?;
+				self.wait_for(PUBCOMP, Some(id))
+			},
+			_ => Ok(()),
+		}
+	}
+
+	pub fn ping(&mut self) -> Result<(), MqttError>
+	{
+		self.send(PINGREQ, &[])?;
+		self.wait_for(PINGRESP, None)
+	}
+
+	pub fn disconnect(mut self) -> Result<(), MqttError>
+	{
+		self.send(DISCONNECT, &[])
+	}
+}
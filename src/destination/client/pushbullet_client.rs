@@ -0,0 +1,85 @@
+use reqwest::{Client, StatusCode};
+use serde_json::Value;
+
+const API_URL: &'static str = "https://api.pushbullet.com/v2/pushes";
+const ACCESS_TOKEN_HEADER: &'static str = "Access-Token";
+
+pub struct PushBulletClient {
+	access_token: String,
+	device_iden: Option<String>,
+	channel_tag: Option<String>,
+	client: Client,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum PushBulletError {
+	Network,
+	Unauthorized,
+	RateLimitExceeded,
+	InvalidRequest,
+	Unknown(String),
+}
+
+impl PushBulletError {
+
+	pub fn check_status(status: StatusCode) -> Result<(), PushBulletError>
+	{
+		match status {
+			StatusCode::OK => Ok(()),
+			StatusCode::BAD_REQUEST => Err(PushBulletError::InvalidRequest),
+			StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(PushBulletError::Unauthorized),
+			StatusCode::TOO_MANY_REQUESTS => Err(PushBulletError::RateLimitExceeded),
+			_ => Err(PushBulletError::Unknown(format!("unknown status: {}", status)))
+		}
+	}
+}
+
+impl PushBulletClient {
+
+	pub fn new(access_token: String, device_iden: Option<String>, channel_tag: Option<String>)
+		-> PushBulletClient
+	{
+		PushBulletClient {
+			access_token: access_token,
+			device_iden: device_iden,
+			channel_tag: channel_tag,
+			client: Client::new(),
+		}
+	}
+
+	pub fn is_valid(&self) -> bool
+	{
+		! self.access_token.is_empty() && self.access_token.is_ascii()
+	}
+
+	// the note is pushed to all devices unless `device_iden` or `channel_tag` is configured
+	pub fn build_note(&self, title: &str, body: &str) -> Value
+	{
+		let mut payload = json!({
+			"type": "note",
+			"title": title,
+			"body": body,
+		});
+
+		if let Some(ref device_iden) = self.device_iden {
+			payload["device_iden"] = json!(device_iden);
+		}
+
+		if let Some(ref channel_tag) = self.channel_tag {
+			payload["channel_tag"] = json!(channel_tag);
+		}
+
+		payload
+	}
+
+	pub fn push(&self, payload: &Value) -> Result<(), PushBulletError>
+	{
+		let response = self.client.post(API_URL)
+			.header(ACCESS_TOKEN_HEADER, self.access_token.as_str())
+			.json(payload)
+			.send()
+			.map_err(|_| PushBulletError::Network)?;
+
+		PushBulletError::check_status(response.status())
+	}
+}
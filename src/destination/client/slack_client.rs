@@ -1,17 +1,55 @@
 use reqwest::{Client, Url, StatusCode};
+use serde_json::Value;
 
 const WARNING_COLOR: &'static str = "#CF0301";
 const INFO_COLOR: &'static str = "#E8E8E8";
 
+const POST_MESSAGE_URL: &'static str = "https://slack.com/api/chat.postMessage";
+const UPDATE_URL: &'static str = "https://slack.com/api/chat.update";
+
+enum SlackTarget {
+	Webhook(Url),
+	Bot { token: String, channel: String },
+}
+
 pub struct SlackClient {
-	webhook_url: Url,
+	target: SlackTarget,
 	client: Client,
 }
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Debug, Clone)]
 pub enum SlackError {
 	Network,
 	Rejected,
+	InvalidResponse,
+	Api(String),
+	Unsupported,
+}
+
+impl SlackError {
+
+	pub fn check_webhook_status(status: StatusCode) -> Result<(), SlackError>
+	{
+		match status {
+			StatusCode::OK => Ok(()),
+			_ => Err(SlackError::Rejected),
+		}
+	}
+
+	// the Web API reports errors with `"ok": false` and HTTP 200
+	pub fn check_api_result(result: Value) -> Result<Value, SlackError>
+	{
+		match result["ok"].as_bool() {
+			Some(true) => Ok(result),
+			_ => Err(SlackError::Api(result["error"].as_str().unwrap_or("unknown").to_owned())),
+		}
+	}
+
+	// the message has been deleted on the Slack side
+	pub fn is_message_lost(&self) -> bool
+	{
+		*self == SlackError::Api("message_not_found".to_owned())
+	}
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
@@ -20,6 +58,40 @@ pub enum SlackMessageType {
 	Info,
 }
 
+// identifies a message posted via the Web API
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct SlackMessage {
+	pub channel: String,
+	pub ts: String,
+}
+
+impl SlackMessage {
+
+	// from the result of chat.postMessage
+	pub fn from_api_result(result: &Value) -> Result<SlackMessage, SlackError>
+	{
+		match (result["channel"].as_str(), result["ts"].as_str()) {
+			(Some(channel), Some(ts)) => Ok(SlackMessage { channel: channel.to_owned(), ts: ts.to_owned() }),
+			_ => Err(SlackError::InvalidResponse),
+		}
+	}
+}
+
+fn build_attachments(body: &str, footer: &str, msg_type: SlackMessageType) -> Value
+{
+	let color = match msg_type {
+		SlackMessageType::Warning => WARNING_COLOR,
+		SlackMessageType::Info => INFO_COLOR,
+	};
+
+	json!([{
+		"fallback" : body,
+		"text" : body,
+		"footer": footer,
+		"color" : color,
+	}])
+}
+
 impl SlackClient {
 
 	pub fn build(webhook_url: &str) -> Result<SlackClient, ()>
@@ -27,36 +99,114 @@ impl SlackClient {
 		let webhook_url = Url::parse(webhook_url).map_err(|_| ())?;
 
 		Ok(SlackClient {
-			webhook_url: webhook_url,
+			target: SlackTarget::Webhook(webhook_url),
 			client: Client::new(),
 		})
 	}
 
+	pub fn with_bot_token(token: String, channel: String) -> SlackClient
+	{
+		SlackClient {
+			target: SlackTarget::Bot { token: token, channel: channel },
+			client: Client::new(),
+		}
+	}
+
+	pub fn is_valid(&self) -> bool
+	{
+		match self.target {
+			SlackTarget::Webhook(_) => true,
+			SlackTarget::Bot { ref token, ref channel } =>
+				! token.is_empty() && token.is_ascii() && ! channel.is_empty(),
+		}
+	}
+
+	// whether messages can be updated with `update_message`
+	pub fn is_bot(&self) -> bool
+	{
+		match self.target {
+			SlackTarget::Webhook(_) => false,
+			SlackTarget::Bot { .. } => true,
+		}
+	}
+
 	pub fn post_message(&self, body: &str, footer: &str,
 		msg_type: SlackMessageType) -> Result<(), SlackError>
 	{
-		let color = match msg_type {
-			SlackMessageType::Warning => WARNING_COLOR,
-			SlackMessageType::Info => INFO_COLOR,
+		match self.target {
+
+			SlackTarget::Webhook(ref webhook_url) => {
+
+				let payload = json!({
+					"attachments" : build_attachments(body, footer, msg_type),
+				});
+
+				let response = self.client.post(webhook_url.clone())
+					.json(&payload)
+					.send()
+					.map_err(|_| SlackError::Network)?;
+
+				SlackError::check_webhook_status(response.status())
+			},
+
+			SlackTarget::Bot { .. } => self.post_bot_message(body, footer, msg_type, None).map(|_| ()),
+		}
+	}
+
+	// posts a message via chat.postMessage; `thread_ts` makes it a reply in the thread
+	pub fn post_bot_message(&self, body: &str, footer: &str, msg_type: SlackMessageType,
+		thread_ts: Option<&str>) -> Result<SlackMessage, SlackError>
+	{
+		let channel = match self.target {
+			SlackTarget::Bot { ref channel, .. } => channel,
+			SlackTarget::Webhook(_) => return Err(SlackError::Unsupported),
 		};
 
+		let mut payload = json!({
+			"channel": channel,
+			"text": body,
+			"attachments": build_attachments(body, footer, msg_type),
+		});
+
+		if let Some(ts) = thread_ts {
+			payload["thread_ts"] = json!(ts);
+		}
+
+		let result = self.call_api(POST_MESSAGE_URL, &payload)?;
+		SlackMessage::from_api_result(&result)
+	}
+
+	pub fn update_message(&self, msg: &SlackMessage, body: &str, footer: &str,
+		msg_type: SlackMessageType) -> Result<(), SlackError>
+	{
 		let payload = json!({
-			"attachments" : [{
-				"fallback" : body,
-				"text" : body,
-				"footer": footer,
-				"color" : color,
-			}]
+			"channel": msg.channel,
+			"ts": msg.ts,
+			"text": body,
+			"attachments": build_attachments(body, footer, msg_type),
 		});
 
-		let response = self.client.post(self.webhook_url.clone())
-			.json(&payload)
+		self.call_api(UPDATE_URL, &payload).map(|_| ())
+	}
+
+	fn call_api(&self, url: &str, payload: &Value) -> Result<Value, SlackError>
+	{
+		let token = match self.target {
+			SlackTarget::Bot { ref token, .. } => token,
+			SlackTarget::Webhook(_) => return Err(SlackError::Unsupported),
+		};
+
+		let mut response = self.client.post(url)
+			.bearer_auth(token)
+			.json(payload)
 			.send()
 			.map_err(|_| SlackError::Network)?;
 
-		match response.status() {
-			StatusCode::OK => Ok(()),
-			_ => Err(SlackError::Rejected),
+		if response.status() != StatusCode::OK {
+			return Err(SlackError::Rejected);
 		}
+
+		let result: Value = response.json().map_err(|_| SlackError::InvalidResponse)?;
+		SlackError::check_api_result(result)
 	}
 }
@@ -28,6 +28,11 @@ pub enum TwitterError {
 	Unknown(String),
 }
 
+fn is_token(s: &str) -> bool
+{
+	! s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
 impl TwitterClient {
 
 	pub fn new(consumer_key: String, consumer_secret: String, access_key: String, access_secret: String) -> TwitterClient
@@ -43,8 +48,14 @@ impl TwitterClient {
 
 	pub fn is_valid(&self) -> bool
 	{
-		// TODO: implement token validation
-		true
+		// access tokens are in the form of `<user id>-<token>`
+		let access_key_valid = match self.access_key.split_once('-') {
+			Some((user_id, token)) => ! user_id.is_empty() && user_id.chars().all(|c| c.is_ascii_digit()) && is_token(token),
+			None => false,
+		};
+
+		is_token(&self.consumer_key) && is_token(&self.consumer_secret) &&
+			access_key_valid && is_token(&self.access_secret)
 	}
 
 	fn post(&self, url: &str, args: Vec<(&str, &str)>) -> Result<Response, ()>
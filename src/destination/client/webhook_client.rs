@@ -0,0 +1,53 @@
+use std::time::Duration;
+use std::collections::HashMap;
+
+use serde_json::Value;
+use reqwest::{Client, Url};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+pub struct WebhookClient {
+	url: Url,
+	client: Client,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum WebhookError {
+	Network,
+	Rejected(u16),
+}
+
+impl WebhookClient {
+
+	pub fn build(url: &str, headers: &HashMap<String, String>, timeout: Duration) -> Result<WebhookClient, ()>
+	{
+		let url = Url::parse(url).map_err(|_| ())?;
+		let mut header_map = HeaderMap::new();
+
+		for (name, value) in headers.iter() {
+			let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| ())?;
+			let value = HeaderValue::from_str(value).map_err(|_| ())?;
+			header_map.insert(name, value);
+		}
+
+		let client = Client::builder()
+			.timeout(timeout)
+			.default_headers(header_map)
+			.build()
+			.map_err(|_| ())?;
+
+		Ok(WebhookClient { url: url, client: client })
+	}
+
+	pub fn post(&self, payload: &Value) -> Result<(), WebhookError>
+	{
+		let response = self.client.post(self.url.clone())
+			.json(payload)
+			.send()
+			.map_err(|_| WebhookError::Network)?;
+
+		match response.status() {
+			s if s.is_success() => Ok(()),
+			s => Err(WebhookError::Rejected(s.as_u16())),
+		}
+	}
+}
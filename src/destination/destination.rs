@@ -1,3 +1,5 @@
+use serde_json::Value;
+
 use eew::EEW;
 
 pub trait Destination {
@@ -6,4 +8,8 @@ pub trait Destination {
 	fn emit(&mut self, latest: &EEW, prev: Option<&EEW>);
 	fn wake(&mut self) { }
 
+	// the state for the event `id` to be kept across restarts (e.g. the ID of the posted message)
+	fn state(&self, _id: &str) -> Option<Value> { None }
+	fn restore_state(&mut self, _id: &str, _state: Value) { }
+
 }
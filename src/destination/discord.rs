@@ -0,0 +1,226 @@
+use serde_json::Value;
+
+use eew::{EEW, EEWPhase};
+use destination::client::DiscordClient;
+use destination::Destination;
+use collections::IndexedLimitedQueue;
+use translator::{Translator, Language};
+
+const ALERT_COLOR: u32 = 0xCF0301;
+const FORECAST_COLOR: u32 = 0xF2B705;
+const FAST_FORECAST_COLOR: u32 = 0xE8E8E8;
+const CANCEL_COLOR: u32 = 0x808080;
+
+pub struct Discord {
+	client: DiscordClient,
+	latest_msg_ids: IndexedLimitedQueue<String>,
+	updown_enabled: bool,
+	translator: Translator,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+enum DiscordAction {
+	Post,
+	Edit(String),
+}
+
+impl DiscordAction {
+
+	// one message is kept up to date per EEW ID
+	fn decide(prev_msg_id: Option<&String>) -> DiscordAction
+	{
+		match prev_msg_id {
+			Some(msg_id) => DiscordAction::Edit(msg_id.clone()),
+			None => DiscordAction::Post,
+		}
+	}
+}
+
+// epicenter, magnitude, depth, maximum intensity and number
+fn field_names(language: Language) -> [&'static str; 5]
+{
+	match language {
+		Language::Japanese => ["震源", "規模", "深さ", "最大震度", "報数"],
+		Language::English => ["Epicenter", "Magnitude", "Depth", "Max Intensity", "Number"],
+	}
+}
+
+fn build_embed(translator: &Translator, latest: &EEW, prev: Option<&EEW>) -> Option<Value>
+{
+	let out = translator.format_eew_short(latest, prev)?;
+	let names = field_names(translator.language());
+
+	let color = match latest.get_eew_phase() {
+		Some(EEWPhase::Alert) => ALERT_COLOR,
+		Some(EEWPhase::Forecast) => FORECAST_COLOR,
+		Some(EEWPhase::FastForecast) => FAST_FORECAST_COLOR,
+		Some(EEWPhase::Cancel) | None => CANCEL_COLOR,
+	};
+
+	let mut fields = Vec::new();
+
+	if let Some(ref detail) = translator.localize(latest).detail {
+		fields.push(json!({ "name": names[0], "value": detail.epicenter_name, "inline": true }));
+		fields.push(json!({ "name": names[1], "value": translator.format_magnitude(detail.magnitude), "inline": true }));
+		fields.push(json!({ "name": names[2], "value": translator.format_depth(detail.depth), "inline": true }));
+		fields.push(json!({ "name": names[3],
+			"value": translator.format_intensity(detail.maximum_intensity), "inline": true }));
+	}
+
+	fields.push(json!({ "name": names[4], "value": translator.format_number(latest.number), "inline": true }));
+
+	Some(json!({
+		"title": out.0,
+		"description": out.1,
+		"color": color,
+		"fields": fields,
+		"footer": { "text": out.2 },
+		"timestamp": latest.issued_at.to_rfc3339(),
+	}))
+}
+
+impl Discord {
+
+	pub fn build(webhook_url: &str, updown_enabled: bool, translator: Translator) -> Result<Discord, ()>
+	{
+		let client = DiscordClient::build(webhook_url)?;
+		let q = IndexedLimitedQueue::new(16);
+
+		Ok(Discord { client: client, latest_msg_ids: q, updown_enabled: updown_enabled,
+			translator: translator })
+	}
+
+	fn post(&mut self, id: &str, embed: &Value)
+	{
+		match self.client.post_embed(embed) {
+
+			Ok(msg_id) => {
+				self.latest_msg_ids.upsert(id.to_owned(), msg_id);
+			},
+
+			Err(e) => {
+				error!("DiscordError: {:?}", e);
+			}
+		}
+	}
+}
+
+impl Destination for Discord {
+
+	fn emit(&mut self, latest: &EEW, prev: Option<&EEW>)
+	{
+		let prev = match self.updown_enabled {
+			true => prev,
+			false => None,
+		};
+
+		let embed = match build_embed(&self.translator, latest, prev) {
+			Some(embed) => embed,
+			None => return
+		};
+
+		match DiscordAction::decide(self.latest_msg_ids.get(latest.id.as_ref())) {
+
+			DiscordAction::Edit(msg_id) => match self.client.edit_embed(&msg_id, &embed) {
+
+				Ok(_) => {},
+
+				Err(ref e) if e.is_message_lost() => {
+					warn!("Discord: message {} not found; posting a new one", msg_id);
+					self.post(&latest.id, &embed);
+				},
+
+				Err(e) => {
+					error!("DiscordError: {:?}", e);
+				}
+			},
+
+			DiscordAction::Post => self.post(&latest.id, &embed),
+		}
+	}
+
+	fn state(&self, id: &str) -> Option<Value>
+	{
+		self.latest_msg_ids.get(id).map(|msg_id| json!(msg_id))
+	}
+
+	fn restore_state(&mut self, id: &str, state: Value)
+	{
+		if let Some(msg_id) = state.as_str() {
+			self.latest_msg_ids.upsert(id.to_owned(), msg_id.to_owned());
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use chrono::{TimeZone, Utc};
+
+	use eew::Kind;
+	use source::synthetic_eews;
+	use super::*;
+
+	#[test]
+	fn it_should_edit_the_message_of_the_same_id()
+	{
+		assert_eq!(DiscordAction::decide(None), DiscordAction::Post);
+		assert_eq!(DiscordAction::decide(Some(&"1234".to_owned())), DiscordAction::Edit("1234".to_owned()));
+	}
+
+	fn embed(i: usize) -> Value
+	{
+		let eews = synthetic_eews(Utc.ymd(2024, 1, 1).and_hms(0, 0, 0), Kind::Trial);
+		build_embed(&Translator::default(), &eews[i], None).unwrap()
+	}
+
+	fn field(name: &str, value: &str) -> Value
+	{
+		json!({ "name": name, "value": value, "inline": true })
+	}
+
+	#[test]
+	fn it_should_build_forecast_embeds()
+	{
+		assert_eq!(embed(0), json!({
+			"title": "テスト配信 | 予報",
+			"description": "茨城県南部 震度3 M4.6 50km (N36.1/E139.9) 09:00:00発生",
+			"color": FORECAST_COLOR,
+			"fields": [
+				field("震源", "茨城県南部"), field("規模", "M4.6"), field("深さ", "50km"),
+				field("最大震度", "震度3"), field("報数", "第1報"),
+			],
+			"footer": { "text": "第1報 ND20240101000000" },
+			"timestamp": "2024-01-01T00:00:05+00:00",
+		}));
+	}
+
+	#[test]
+	fn it_should_build_alert_embeds()
+	{
+		assert_eq!(embed(2), json!({
+			"title": "テスト配信 | 警報",
+			"description": "茨城県南部 震度5弱 M6.0 50km (N36.1/E139.9) 09:00:00発生",
+			"color": ALERT_COLOR,
+			"fields": [
+				field("震源", "茨城県南部"), field("規模", "M6.0"), field("深さ", "50km"),
+				field("最大震度", "震度5弱"), field("報数", "第3報"),
+			],
+			"footer": { "text": "第3報 ND20240101000000" },
+			"timestamp": "2024-01-01T00:00:07+00:00",
+		}));
+	}
+
+	#[test]
+	fn it_should_build_cancel_embeds_without_details()
+	{
+		assert_eq!(embed(5), json!({
+			"title": "テスト配信 | 取消",
+			"description": "---",
+			"color": CANCEL_COLOR,
+			"fields": [field("報数", "第2報")],
+			"footer": { "text": "第2報 ND20240101000100" },
+			"timestamp": "2024-01-01T00:01:08+00:00",
+		}));
+	}
+}
@@ -0,0 +1,130 @@
+use std::io::Write;
+use std::process::{Command, Stdio, Child, ExitStatus};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::{self, sleep};
+use std::time::{Duration, Instant};
+
+use eew::EEW;
+use destination::Destination;
+use translator::{format_eew_json, json_format_phase};
+
+const POLL_INTERVAL_MS: u64 = 100;
+
+pub struct Exec {
+	program: String,
+	args: Vec<String>,
+	timeout: Duration,
+	max_concurrency: usize,
+	running: Arc<AtomicUsize>,
+}
+
+fn build_env_vars(latest: &EEW, prev: Option<&EEW>) -> Vec<(&'static str, String)>
+{
+	let to_s = |v: Option<String>| v.unwrap_or_default();
+	let flag = |v: bool| (if v { "1" } else { "0" }).to_owned();
+	let detail = latest.detail.as_ref();
+	let prev_detail = prev.and_then(|p| p.detail.as_ref());
+
+	vec![
+		("TINA_EEW_ID", latest.id.clone()),
+		("TINA_EEW_NUMBER", latest.number.to_string()),
+		("TINA_PHASE", to_s(latest.get_eew_phase().map(|p| json_format_phase(p).to_owned()))),
+		("TINA_ISSUED_AT", latest.issued_at.to_rfc3339()),
+		("TINA_OCCURRED_AT", latest.occurred_at.to_rfc3339()),
+		("TINA_IS_FIRST", flag(prev.is_none())),
+		("TINA_IS_LAST", flag(latest.is_last())),
+		("TINA_IS_DRILL", flag(latest.is_drill())),
+		("TINA_IS_TEST", flag(latest.is_test())),
+		("TINA_EPICENTER_NAME", to_s(detail.map(|d| d.epicenter_name.clone()))),
+		("TINA_LATITUDE", to_s(detail.map(|d| d.epicenter.0.to_string()))),
+		("TINA_LONGITUDE", to_s(detail.map(|d| d.epicenter.1.to_string()))),
+		("TINA_DEPTH", to_s(detail.and_then(|d| d.depth).map(|v| v.to_string()))),
+		("TINA_MAGNITUDE", to_s(detail.and_then(|d| d.magnitude).map(|v| v.to_string()))),
+		("TINA_MAX_INTENSITY",
+			to_s(detail.and_then(|d| d.maximum_intensity).map(|i| i.as_jma_str().to_owned()))),
+		("TINA_PREV_MAX_INTENSITY",
+			to_s(prev_detail.and_then(|d| d.maximum_intensity).map(|i| i.as_jma_str().to_owned()))),
+	]
+}
+
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> Option<ExitStatus>
+{
+	let started = Instant::now();
+
+	loop {
+		match child.try_wait() {
+			Ok(Some(status)) => return Some(status),
+			Ok(None) if started.elapsed() < timeout => sleep(Duration::from_millis(POLL_INTERVAL_MS)),
+			_ => return None,
+		}
+	}
+}
+
+impl Exec {
+
+	pub fn new(program: String, args: Vec<String>, timeout: Duration, max_concurrency: usize) -> Exec
+	{
+		Exec {
+			program: program,
+			args: args,
+			timeout: timeout,
+			max_concurrency: max_concurrency,
+			running: Arc::new(AtomicUsize::new(0)),
+		}
+	}
+}
+
+impl Destination for Exec {
+
+	fn emit(&mut self, latest: &EEW, prev: Option<&EEW>)
+	{
+		if self.running.load(Ordering::SeqCst) >= self.max_concurrency {
+			warn!("Exec: {} skipped (too many running processes)", self.program);
+			return;
+		}
+
+		let spawned = Command::new(&self.program)
+			.args(&self.args)
+			.envs(build_env_vars(latest, prev))
+			.stdin(Stdio::piped())
+			.stdout(Stdio::null())
+			.spawn();
+
+		let mut child = match spawned {
+			Ok(child) => child,
+			Err(e) => {
+				error!("ExecError: failed to spawn {} ({})", self.program, e);
+				return;
+			}
+		};
+
+		let input = format_eew_json(latest, prev).to_string();
+		let program = self.program.clone();
+		let timeout = self.timeout;
+		let running = self.running.clone();
+
+		running.fetch_add(1, Ordering::SeqCst);
+
+		thread::spawn(move || {
+
+			// writing on another thread keeps the timeout working even if the child never reads stdin;
+			// the write fails (and the thread exits) when the child exits or gets killed
+			if let Some(mut stdin) = child.stdin.take() {
+				thread::spawn(move || { let _ = stdin.write_all(input.as_bytes()); });
+			}
+
+			match wait_with_timeout(&mut child, timeout) {
+				Some(status) if status.success() => info!("Exec: {} exited ({})", program, status),
+				Some(status) => warn!("Exec: {} exited ({})", program, status),
+				None => {
+					let _ = child.kill();
+					let _ = child.wait();
+					error!("ExecError: {} killed (timed out)", program);
+				}
+			}
+
+			running.fetch_sub(1, Ordering::SeqCst);
+		});
+	}
+}
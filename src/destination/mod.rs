@@ -2,11 +2,24 @@ mod router;
 mod client;
 mod twitter;
 mod slack;
+mod pushbullet;
+mod discord;
+mod mqtt;
+mod stream;
+mod webhook;
+mod exec;
 mod logging;
 mod destination;
 
-pub use self::router::{Router, Routing};
+pub use self::router::{Router, Routing, RouteResult};
 pub use self::twitter::Twitter;
-pub use self::slack::Slack;
+pub use self::slack::{Slack, SlackThread, SlackBotAction};
+pub use self::pushbullet::PushBullet;
+pub use self::discord::Discord;
+pub use self::mqtt::Mqtt;
+pub use self::stream::EEWStream;
+pub use self::webhook::Webhook;
+pub use self::exec::Exec;
 pub use self::logging::Logging;
 pub use self::destination::Destination;
+pub use self::client::{SlackError, SlackMessage, PushBulletError};
@@ -0,0 +1,228 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use eew::EEW;
+use destination::client::{MqttClient, MqttWill};
+use destination::Destination;
+use moderator::Moderator;
+use translator::format_eew_json;
+
+const KEEP_ALIVE_SECS: u16 = 60;
+const NETWORK_TIMEOUT_SECS: u64 = 10;
+const RETRY_MAX_COUNT: u32 = 6;
+const RETRY_RATE: u32 = 2;
+// EEWs not published within this period are dropped since they are too late to be useful
+const MAX_PENDING_SECS: u64 = 60;
+
+const ONLINE_PAYLOAD: &'static [u8] = b"online";
+const OFFLINE_PAYLOAD: &'static [u8] = b"offline";
+
+// an EEW waiting for the connection to the broker
+struct Pending {
+	id: String,
+	topics: Vec<String>,
+	payload: Vec<u8>,
+	queued_at: Instant,
+}
+
+pub struct Mqtt {
+	addr: String,
+	client_id: String,
+	credentials: Option<(String, String)>,
+	topic_prefix: String,
+	qos: u8,
+	retain: bool,
+	client: Option<MqttClient>,
+	moderator: Moderator,
+	next_retry_at: Option<Instant>,
+	pending: VecDeque<Pending>,
+}
+
+impl Mqtt {
+
+	pub fn new(host: String, port: u16, client_id: String, credentials: Option<(String, String)>,
+		topic_prefix: String, qos: u8, retain: bool) -> Mqtt
+	{
+		Mqtt {
+			addr: format!("{}:{}", host, port),
+			client_id: client_id,
+			credentials: credentials,
+			topic_prefix: topic_prefix.trim_end_matches('/').to_owned(),
+			qos: qos,
+			retain: retain,
+			client: None,
+			moderator: Moderator::with_custom_rate(RETRY_MAX_COUNT, RETRY_RATE),
+			next_retry_at: None,
+			pending: VecDeque::new(),
+		}
+	}
+
+	pub fn is_valid(&self) -> bool
+	{
+		self.qos <= 2 && ! self.client_id.is_empty() &&
+			! self.topic_prefix.is_empty() && ! self.topic_prefix.contains(&['#', '+'][..])
+	}
+
+	fn status_topic(&self) -> String
+	{
+		format!("{}/status", self.topic_prefix)
+	}
+
+	fn connect(&mut self) -> bool
+	{
+		let will = MqttWill {
+			topic: self.status_topic(),
+			payload: OFFLINE_PAYLOAD.to_vec(),
+			qos: self.qos,
+			retain: true,
+		};
+
+		let credentials = self.credentials.as_ref().map(|c| (c.0.as_str(), c.1.as_str()));
+		let timeout = Duration::from_secs(NETWORK_TIMEOUT_SECS);
+
+		let result = MqttClient::connect(&self.addr, &self.client_id, credentials,
+			KEEP_ALIVE_SECS, Some(&will), timeout)
+			.and_then(|mut c| c.publish(&will.topic, ONLINE_PAYLOAD, self.qos, true).map(|_| c));
+
+		match result {
+			Ok(c) => {
+				info!("Mqtt: connected to {}", self.addr);
+				self.client = Some(c);
+				self.moderator.reset();
+				true
+			},
+			Err(e) => {
+				error!("MqttError: {:?} (connecting to {})", e, self.addr);
+				self.moderator.add_count();
+				false
+			}
+		}
+	}
+
+	// connects once unless waiting for the backoff;
+	// this never sleeps so that EEWs are not delayed behind the backoff on the destination thread
+	fn try_connect(&mut self) -> bool
+	{
+		if self.client.is_some() {
+			return true;
+		}
+
+		if let Some(t) = self.next_retry_at {
+			if Instant::now() < t {
+				return false;
+			}
+		}
+
+		let connected = self.connect();
+		let interval = Duration::from_secs(self.moderator.compute_next_interval() as u64);
+		self.next_retry_at = if connected { None } else { Some(Instant::now() + interval) };
+
+		connected
+	}
+
+	// publishes the pending EEWs in order; at most one connection attempt is made
+	// so that the time blocked is bounded by NETWORK_TIMEOUT_SECS
+	fn flush(&mut self)
+	{
+		let max_age = Duration::from_secs(MAX_PENDING_SECS);
+		let mut connect_tried = false;
+
+		while let Some(p) = self.pending.pop_front() {
+
+			if p.queued_at.elapsed() > max_age {
+				error!("Mqtt: dropped {} (not published within {} secs)", p.id, MAX_PENDING_SECS);
+				continue;
+			}
+
+			if self.client.is_none() {
+				if connect_tried || ! self.try_connect() {
+					self.pending.push_front(p);
+					break;
+				}
+				connect_tried = true;
+			}
+
+			// the connection may have been lost silently; the EEW is retried after reconnecting
+			if ! self.publish_all(&p.topics, &p.payload) {
+				self.pending.push_front(p);
+			}
+		}
+
+		if ! self.pending.is_empty() {
+			warn!("Mqtt: {} EEW(s) pending (not connected to {})", self.pending.len(), self.addr);
+		}
+	}
+
+	fn publish_all(&mut self, topics: &[String], payload: &[u8]) -> bool
+	{
+		let (qos, retain) = (self.qos, self.retain);
+
+		let client = match self.client.as_mut() {
+			Some(c) => c,
+			None => return false,
+		};
+
+		for topic in topics.iter() {
+			if let Err(e) = client.publish(topic, payload, qos, retain) {
+				error!("MqttError: {:?} (publishing to {})", e, topic);
+				self.client = None;
+				return false;
+			}
+		}
+
+		true
+	}
+}
+
+impl Destination for Mqtt {
+
+	const WAKE_TIMEOUT_SECS: u64 = (KEEP_ALIVE_SECS / 2) as u64;
+
+	fn emit(&mut self, latest: &EEW, prev: Option<&EEW>)
+	{
+		let topics = vec![
+			format!("{}/eew/{}", self.topic_prefix, latest.id),
+			format!("{}/eew/latest", self.topic_prefix),
+		];
+
+		self.pending.push_back(Pending {
+			id: latest.id.clone(),
+			topics: topics,
+			payload: format_eew_json(latest, prev).to_string().into_bytes(),
+			queued_at: Instant::now(),
+		});
+
+		self.flush();
+	}
+
+	fn wake(&mut self)
+	{
+		if let Some(ref mut c) = self.client {
+			if c.ping().is_ok() {
+				return;
+			}
+			warn!("Mqtt: connection lost");
+		}
+
+		self.client = None;
+
+		match self.pending.is_empty() {
+			true => { self.try_connect(); },
+			false => self.flush(),
+		}
+	}
+}
+
+impl Drop for Mqtt {
+
+	fn drop(&mut self)
+	{
+		let topic = self.status_topic();
+
+		if let Some(mut c) = self.client.take() {
+			// the will message is not sent on a clean disconnection
+			let _ = c.publish(&topic, OFFLINE_PAYLOAD, self.qos, true);
+			let _ = c.disconnect();
+		}
+	}
+}
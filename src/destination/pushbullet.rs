@@ -0,0 +1,102 @@
+use serde_json::Value;
+
+use eew::EEW;
+use destination::client::PushBulletClient;
+use destination::Destination;
+use translator::Translator;
+
+pub struct PushBullet {
+	client: PushBulletClient,
+	updown_enabled: bool,
+	translator: Translator,
+}
+
+impl PushBullet {
+
+	pub fn new(access_token: String, device_iden: Option<String>, channel_tag: Option<String>,
+		updown_enabled: bool, translator: Translator) -> PushBullet
+	{
+		let client = PushBulletClient::new(access_token, device_iden, channel_tag);
+		PushBullet { client: client, updown_enabled: updown_enabled, translator: translator }
+	}
+
+	pub fn is_valid(&self) -> bool
+	{
+		self.client.is_valid()
+	}
+
+	fn build_note(&self, latest: &EEW, prev: Option<&EEW>) -> Option<Value>
+	{
+		let prev = match self.updown_enabled {
+			true => prev,
+			false => None,
+		};
+
+		let out = self.translator.format_eew_short(latest, prev)?;
+		let title = format!("[{}] {}", out.0, out.2);
+
+		Some(self.client.build_note(&title, &out.1))
+	}
+}
+
+impl Destination for PushBullet {
+
+	fn emit(&mut self, latest: &EEW, prev: Option<&EEW>)
+	{
+		let payload = match self.build_note(latest, prev) {
+			Some(payload) => payload,
+			None => return
+		};
+
+		match self.client.push(&payload) {
+
+			Ok(_) => {}
+
+			Err(e) => {
+				error!("PushBulletError: {:?}", e);
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+
+	use chrono::{TimeZone, Utc};
+
+	use eew::Kind;
+	use source::synthetic_eews;
+	use super::*;
+
+	fn eews() -> Vec<EEW>
+	{
+		synthetic_eews(Utc.ymd(2024, 1, 1).and_hms(0, 0, 0), Kind::Trial)
+	}
+
+	#[test]
+	fn it_should_build_a_note_to_all_devices()
+	{
+		let pb = PushBullet::new("o.token".to_owned(), None, None, true, Translator::default());
+		let eews = eews();
+
+		assert_eq!(pb.build_note(&eews[1], Some(&eews[0])), Some(json!({
+			"type": "note",
+			"title": "[テスト配信 | 予報↑] 第2報 ND20240101000000",
+			"body": "茨城県南部 震度4 M5.4 50km (N36.1/E139.9) 09:00:00発生",
+		})));
+	}
+
+	#[test]
+	fn it_should_build_a_targeted_note()
+	{
+		let pb = PushBullet::new("o.token".to_owned(), Some("device".to_owned()), Some("channel".to_owned()),
+			false, Translator::default());
+		let eews = eews();
+		let note = pb.build_note(&eews[1], Some(&eews[0])).unwrap();
+
+		// `updown_enabled` is false
+		assert_eq!(note["title"], "[テスト配信 | 予報] 第2報 ND20240101000000");
+		assert_eq!(note["device_iden"], "device");
+		assert_eq!(note["channel_tag"], "channel");
+	}
+}
@@ -1,5 +1,6 @@
 use std::marker::Send;
 use std::thread;
+use std::thread::JoinHandle;
 use std::sync::mpsc::{SyncSender, sync_channel, RecvTimeoutError};
 use std::sync::Arc;
 use std::time::Duration;
@@ -8,19 +9,39 @@ use eew::EEW;
 use collections::IndexedLimitedQueue;
 use destination::Destination;
 use condition::Condition;
+use metrics::Metrics;
+use journal::Journal;
 
 const CHANNEL_SIZE: usize = 256;
 const EEW_BUFFER_SIZE: usize = 256;
 
+type Message = (Arc<EEW>, Option<Arc<EEW>>);
+
+// what the router did with an EEW (the `result` of the metrics)
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum RouteResult {
+	// passed to the destination thread
+	Sent,
+	Filtered,
+	// the router is closed or the queue is full
+	Failed,
+}
+
 pub struct Router<C> {
 	name: String,
-	tx: SyncSender<(Arc<EEW>, Option<Arc<EEW>>)>,
+	tx: Option<SyncSender<Message>>,
 	cond: C,
 	buffer: IndexedLimitedQueue<Arc<EEW>>,
+	handle: Option<JoinHandle<()>>,
 }
 
 pub trait Routing {
-	fn emit(&mut self, eew: &Arc<EEW>);
+	fn name(&self) -> &str;
+	fn emit(&mut self, eew: &Arc<EEW>) -> RouteResult;
+	// sends the EEW to the destination without checking the condition (e.g. for testing)
+	fn emit_unconditionally(&mut self, eew: &Arc<EEW>) -> RouteResult;
+	// stops accepting EEWs and returns the destination thread, which exits after processing the queued EEWs
+	fn close(&mut self) -> Option<JoinHandle<()>>;
 }
 
 impl<C> Router<C> where C: Condition {
@@ -28,49 +49,153 @@ impl<C> Router<C> where C: Condition {
 	pub fn new<D, S>(dest: D, cond: C, name: S) -> Router<C>
 		where D: Destination + Send + 'static, S: Into<String>
 	{
-		let (tx, rx) = sync_channel::<(Arc<EEW>, Option<Arc<EEW>>)>(CHANNEL_SIZE);
+		Router::with_journal(dest, cond, name, None)
+	}
 
-		thread::spawn(move || {
+	// restores the state of the router and the destination from `journal` and keeps it updated
+	pub fn with_journal<D, S>(dest: D, cond: C, name: S, journal: Option<Journal>) -> Router<C>
+		where D: Destination + Send + 'static, S: Into<String>
+	{
+		Router::with_predecessor(dest, cond, name, journal, None)
+	}
+
+	// takes over from the router of the same destination (e.g. on reloading the config);
+	// EEWs are queued until the thread of `predecessor` finishes so that the states it records
+	// are restored to the destination
+	pub fn with_predecessor<D, S>(dest: D, cond: C, name: S, journal: Option<Journal>,
+		predecessor: Option<JoinHandle<()>>) -> Router<C>
+		where D: Destination + Send + 'static, S: Into<String>
+	{
+		let name = name.into();
+		let mut dest = dest;
+		let mut buffer = IndexedLimitedQueue::new(EEW_BUFFER_SIZE);
+
+		if let Some(ref j) = journal {
+
+			// replaying the conditions reproduces the EEWs which have been sent (used as `prev`)
+			for eew in j.eews() {
+				let satisfied = cond.is_satisfied(&eew, buffer.get(eew.id.as_ref()).map(|arc: &Arc<EEW>| arc.as_ref()));
+				if satisfied {
+					buffer.upsert(eew.id.clone(), Arc::new(eew));
+				}
+			}
+		}
+
+		let (tx, rx) = sync_channel::<Message>(CHANNEL_SIZE);
+		let thread_name = name.clone();
+
+		let handle = thread::spawn(move || {
 
-			let mut dest = dest;
 			let duration = Duration::from_secs(<D as Destination>::WAKE_TIMEOUT_SECS);
 
+			if let Some(handle) = predecessor {
+				if handle.join().is_err() {
+					error!("{}: destination thread of the predecessor panicked", thread_name);
+				}
+			}
+
+			if let Some(ref j) = journal {
+				for (id, state) in j.states(&thread_name) {
+					dest.restore_state(&id, state);
+				}
+			}
+
+			// lets the destination get ready (e.g. connect) without waiting for the first EEW or timeout
+			dest.wake();
+
 			loop {
 				match rx.recv_timeout(duration) {
-					Ok((latest, prev)) =>
-						dest.emit(&latest, prev.as_ref().map(|arc| arc.as_ref())),
+					Ok((latest, prev)) => {
+						dest.emit(&latest, prev.as_ref().map(|arc| arc.as_ref()));
+						if let (Some(j), Some(state)) = (journal.as_ref(), dest.state(&latest.id)) {
+							j.record_state(&thread_name, &latest.id, state);
+						}
+					},
 					Err(RecvTimeoutError::Timeout) => dest.wake(),
-					_ => panic!("eew channel closed unexpectedly")
+					Err(RecvTimeoutError::Disconnected) => break,
 				}
 			}
 		});
 
-		let buffer = IndexedLimitedQueue::new(EEW_BUFFER_SIZE);
+		Router { name: name, tx: Some(tx), cond: cond, buffer: buffer, handle: Some(handle) }
+	}
+}
+
+impl<C> Router<C> {
 
-		Router { name: name.into(), tx: tx, cond: cond, buffer: buffer }
+	fn send(&mut self, eew: &Arc<EEW>) -> RouteResult
+	{
+		let name = &self.name;
+		let old = self.buffer.upsert(eew.id.as_ref(), eew.clone());
+
+		let tx = match self.tx.as_ref() {
+			Some(tx) => tx,
+			None => {
+				warn!("{}: EEW sent to the closed router", name);
+				return RouteResult::Failed;
+			}
+		};
+
+		match tx.try_send((eew.clone(), old)) {
+			Ok(_) => {
+				Metrics::inc_counter("tina_router_eews_total", &[("router", name), ("result", "sent")]);
+				RouteResult::Sent
+			},
+			Err(err) => {
+				warn!("Error while sending EEW data to the destination thread ({:?})", err);
+				Metrics::inc_counter("tina_router_eews_total", &[("router", name), ("result", "failed")]);
+				RouteResult::Failed
+			}
+		}
+	}
+}
+
+impl<C> Drop for Router<C> {
+
+	// waits for the destination thread to process all queued EEWs
+	fn drop(&mut self)
+	{
+		self.tx.take();
+
+		if let Some(handle) = self.handle.take() {
+			if handle.join().is_err() {
+				error!("{}: destination thread panicked", self.name);
+			}
+		}
 	}
 }
 
 impl<C> Routing for Router<C> where C: Condition {
 
-	fn emit(&mut self, eew: &Arc<EEW>)
+	fn name(&self) -> &str
 	{
-		let name = &self.name;
-		let buffer = &mut self.buffer;
+		&self.name
+	}
 
+	fn emit(&mut self, eew: &Arc<EEW>) -> RouteResult
+	{
 		{
-			let prev = buffer.get(eew.id.as_ref());
+			let name = &self.name;
+			let prev = self.buffer.get(eew.id.as_ref());
 
 			if ! self.cond.is_satisfied(eew, prev.map(|arc| arc.as_ref())) {
 				debug!("{}: eew filtered", name);
-				return;
+				Metrics::inc_counter("tina_router_eews_total", &[("router", name), ("result", "filtered")]);
+				return RouteResult::Filtered;
 			}
 		}
 
-		let old = buffer.upsert(eew.id.as_ref(), eew.clone());
+		self.send(eew)
+	}
 
-		if let Err(err) = self.tx.try_send((eew.clone(), old)) {
-			warn!("Error while sending EEW data to the destination thread ({:?})", err);
-		}
+	fn emit_unconditionally(&mut self, eew: &Arc<EEW>) -> RouteResult
+	{
+		self.send(eew)
+	}
+
+	fn close(&mut self) -> Option<JoinHandle<()>>
+	{
+		self.tx.take();
+		self.handle.take()
 	}
 }
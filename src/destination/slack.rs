@@ -1,19 +1,118 @@
+use serde_json;
+use serde_json::Value;
+
 use eew::{EEW, EEWPhase};
-use destination::client::{SlackClient, SlackMessageType};
+use destination::client::{SlackClient, SlackMessage, SlackMessageType};
 use destination::Destination;
-use translator::ja_format_eew_short;
+use collections::IndexedLimitedQueue;
+use translator::Translator;
+
+// the message kept for an EEW ID in the bot-token mode
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+pub struct SlackThread {
+	pub msg: SlackMessage,
+	pub phase: Option<EEWPhase>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum SlackBotAction {
+	Post,
+	// updates the message, and replies in its thread if `reply`
+	Update { msg: SlackMessage, reply: bool },
+}
+
+impl SlackBotAction {
+
+	// the first EEW of an ID is posted; the following ones update it
+	// and are replied in the thread on phase changes and on the last one
+	pub fn decide(thread: Option<&SlackThread>, latest: &EEW) -> SlackBotAction
+	{
+		match thread {
+			Some(thread) => SlackBotAction::Update {
+				msg: thread.msg.clone(),
+				reply: latest.get_eew_phase() != thread.phase || latest.is_last(),
+			},
+			None => SlackBotAction::Post,
+		}
+	}
+}
 
 pub struct Slack {
 	client: SlackClient,
+	latest_threads: IndexedLimitedQueue<SlackThread>,
 	updown_enabled: bool,
+	translator: Translator,
 }
 
 impl Slack {
 
-	pub fn build(webhook_url: &str, updown_enabled: bool) -> Result<Slack, ()>
+	pub fn build(webhook_url: &str, updown_enabled: bool, translator: Translator) -> Result<Slack, ()>
 	{
 		let client = SlackClient::build(webhook_url)?;
-		Ok(Slack { client: client, updown_enabled: updown_enabled })
+		Ok(Slack::with_client(client, updown_enabled, translator))
+	}
+
+	pub fn with_bot_token(token: String, channel: String, updown_enabled: bool, translator: Translator) -> Slack
+	{
+		let client = SlackClient::with_bot_token(token, channel);
+		Slack::with_client(client, updown_enabled, translator)
+	}
+
+	fn with_client(client: SlackClient, updown_enabled: bool, translator: Translator) -> Slack
+	{
+		let q = IndexedLimitedQueue::new(16);
+		Slack { client: client, latest_threads: q, updown_enabled: updown_enabled, translator: translator }
+	}
+
+	pub fn is_valid(&self) -> bool
+	{
+		self.client.is_valid()
+	}
+
+	fn post_thread(&mut self, latest: &EEW, body: &str, footer: &str, msg_type: SlackMessageType)
+	{
+		match self.client.post_bot_message(body, footer, msg_type, None) {
+
+			Ok(msg) => {
+				let thread = SlackThread { msg: msg, phase: latest.get_eew_phase() };
+				self.latest_threads.upsert(latest.id.clone(), thread);
+			},
+
+			Err(e) => {
+				error!("SlackError: {:?}", e);
+			}
+		}
+	}
+
+	// keeps one message per EEW ID up to date and replies in its thread on phase changes
+	fn emit_bot(&mut self, latest: &EEW, body: &str, footer: &str, msg_type: SlackMessageType)
+	{
+		let (msg, reply) = match SlackBotAction::decide(self.latest_threads.get(latest.id.as_ref()), latest) {
+			SlackBotAction::Post => return self.post_thread(latest, body, footer, msg_type),
+			SlackBotAction::Update { msg, reply } => (msg, reply),
+		};
+
+		match self.client.update_message(&msg, body, footer, msg_type) {
+
+			Ok(_) => {},
+
+			Err(ref e) if e.is_message_lost() => {
+				warn!("Slack: message {} not found; posting a new one", msg.ts);
+				return self.post_thread(latest, body, footer, msg_type);
+			},
+
+			Err(e) => {
+				error!("SlackError: {:?}", e);
+			}
+		}
+
+		if reply {
+			if let Err(e) = self.client.post_bot_message(body, footer, msg_type, Some(&msg.ts)) {
+				error!("SlackError: {:?}", e);
+			}
+		}
+
+		self.latest_threads.upsert(latest.id.clone(), SlackThread { msg: msg, phase: latest.get_eew_phase() });
 	}
 }
 
@@ -26,7 +125,7 @@ impl Destination for Slack {
 			false => None,
 		};
 
-		let out = match ja_format_eew_short(latest, prev) {
+		let out = match self.translator.format_eew_short(latest, prev) {
 			Some(out) => out,
 			None => return
 		};
@@ -39,6 +138,10 @@ impl Destination for Slack {
 			_ => SlackMessageType::Info,
 		};
 
+		if self.client.is_bot() {
+			return self.emit_bot(latest, &body, &footer, msg_type);
+		}
+
 		match self.client.post_message(&body, &footer, msg_type) {
 
 			Ok(_) => {}
@@ -48,4 +151,16 @@ impl Destination for Slack {
 			}
 		}
 	}
+
+	fn state(&self, id: &str) -> Option<Value>
+	{
+		self.latest_threads.get(id).and_then(|t| serde_json::to_value(t).ok())
+	}
+
+	fn restore_state(&mut self, id: &str, state: Value)
+	{
+		if let Ok(thread) = serde_json::from_value(state) {
+			self.latest_threads.upsert(id.to_owned(), thread);
+		}
+	}
 }
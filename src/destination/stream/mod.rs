@@ -0,0 +1,347 @@
+mod websocket;
+mod query;
+
+use std::io;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::thread::{spawn, JoinHandle};
+use std::time::{Duration, Instant};
+
+use eew::{EEW, EEWPhase};
+use destination::Destination;
+use collections::IndexedLimitedQueue;
+use condition::{Condition, ValueCondition};
+use translator::format_eew_json;
+
+const STREAM_PATH: &'static str = "/stream";
+const NETWORK_TIMEOUT_SECS: u64 = 5;
+const MAX_HEADER_COUNT: usize = 64;
+const ACTIVE_EVENT_CAPACITY: usize = 16;
+const SENT_BUFFER_SIZE: usize = 16;
+// clients not keeping up with this many pending messages are disconnected
+const CLIENT_QUEUE_SIZE: usize = 32;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum Protocol {
+	ServerSentEvents,
+	WebSocket,
+}
+
+// messages written by the writer thread of each client
+enum Outgoing {
+	Message(String),
+	KeepAlive,
+	Pong(Vec<u8>),
+	Close(Vec<u8>),
+}
+
+struct StreamClient {
+	// used only to shut down the connection; writes are done by the writer thread
+	stream: TcpStream,
+	tx: SyncSender<Outgoing>,
+	cond: ValueCondition,
+	// the latest EEW sent to this client for each ID, used as `prev` like Router
+	sent: IndexedLimitedQueue<EEW>,
+}
+
+struct ActiveEvent {
+	eew: EEW,
+	received_at: Instant,
+}
+
+struct StreamState {
+	clients: Vec<StreamClient>,
+	active: IndexedLimitedQueue<ActiveEvent>,
+}
+
+// pushes EEWs to clients connected to `/stream` via SSE or WebSocket
+pub struct EEWStream {
+	addr: SocketAddr,
+	state: Arc<Mutex<StreamState>>,
+	closed: Arc<AtomicBool>,
+	listener: Option<JoinHandle<()>>,
+}
+
+impl StreamClient {
+
+	// queues `message` without blocking; returns false if the client is gone or too slow
+	fn send(&self, message: Outgoing) -> bool
+	{
+		match self.tx.try_send(message) {
+			Ok(_) => true,
+			Err(TrySendError::Full(_)) => {
+				info!("EEWStream: client disconnected (too slow)");
+				false
+			},
+			Err(TrySendError::Disconnected(_)) => false,
+		}
+	}
+
+	// sends `eew` if it satisfies the condition of this client
+	fn offer(&mut self, eew: &EEW) -> bool
+	{
+		let prev = self.sent.get(eew.id.as_ref()).cloned();
+
+		if ! self.cond.is_satisfied(eew, prev.as_ref()) {
+			return true;
+		}
+
+		self.sent.upsert(eew.id.clone(), eew.clone());
+		self.send(Outgoing::Message(format_eew_json(eew, prev.as_ref()).to_string()))
+	}
+}
+
+impl Drop for StreamClient {
+
+	// also stops the reader thread of WebSocket clients
+	fn drop(&mut self)
+	{
+		let _ = self.stream.shutdown(Shutdown::Both);
+	}
+}
+
+fn encode(protocol: Protocol, message: &Outgoing) -> Vec<u8>
+{
+	match (protocol, message) {
+		(Protocol::ServerSentEvents, Outgoing::Message(text)) =>
+			format!("event: eew\ndata: {}\n\n", text).into_bytes(),
+		(Protocol::ServerSentEvents, Outgoing::KeepAlive) => b": keep-alive\n\n".to_vec(),
+		(Protocol::ServerSentEvents, _) => Vec::new(),
+		(Protocol::WebSocket, Outgoing::Message(text)) => websocket::text_frame(text),
+		(Protocol::WebSocket, Outgoing::KeepAlive) => websocket::ping_frame(),
+		(Protocol::WebSocket, Outgoing::Pong(payload)) => websocket::frame(websocket::OPCODE_PONG, payload),
+		(Protocol::WebSocket, Outgoing::Close(payload)) => websocket::frame(websocket::OPCODE_CLOSE, payload),
+	}
+}
+
+// writes queued messages so that a slow client never blocks the others
+fn write_messages(mut stream: TcpStream, protocol: Protocol, rx: Receiver<Outgoing>)
+{
+	for message in rx.iter() {
+
+		let result = stream.write_all(&encode(protocol, &message)).and_then(|_| stream.flush());
+
+		if let Err(e) = result {
+			info!("EEWStream: client disconnected ({})", e);
+			break;
+		}
+
+		if let Outgoing::Close(_) = message {
+			info!("EEWStream: client disconnected (closed by client)");
+			break;
+		}
+	}
+
+	let _ = stream.shutdown(Shutdown::Both);
+}
+
+// answers pings and close requests from a WebSocket client
+fn read_frames(mut stream: TcpStream, tx: SyncSender<Outgoing>)
+{
+	loop {
+		match websocket::read_frame(&mut stream) {
+			Ok((websocket::OPCODE_PING, payload)) => {
+				if tx.try_send(Outgoing::Pong(payload)).is_err() {
+					return;
+				}
+			},
+			Ok((websocket::OPCODE_CLOSE, mut payload)) => {
+				// echoes only the status code
+				payload.truncate(2);
+				let _ = tx.try_send(Outgoing::Close(payload));
+				return;
+			},
+			Ok(_) => {},
+			Err(_) => return,
+		}
+	}
+}
+
+fn is_active(event: &ActiveEvent, active_duration: Duration) -> bool
+{
+	event.received_at.elapsed() < active_duration && ! event.eew.is_last() &&
+		event.eew.get_eew_phase() != Some(EEWPhase::Cancel)
+}
+
+fn write_error(stream: &mut TcpStream, status: &str, message: &str) -> io::Result<()>
+{
+	write!(stream, "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\
+		Connection: close\r\n\r\n{}", status, message.len(), message)?;
+	stream.flush()
+}
+
+fn accept(stream: TcpStream, state: Arc<Mutex<StreamState>>, active_duration: Duration) -> io::Result<()>
+{
+	let timeout = Some(Duration::from_secs(NETWORK_TIMEOUT_SECS));
+	stream.set_read_timeout(timeout)?;
+	stream.set_write_timeout(timeout)?;
+
+	let mut reader = BufReader::new(stream.try_clone()?);
+	let mut stream = stream;
+
+	let mut request_line = String::new();
+	reader.read_line(&mut request_line)?;
+
+	let mut ws_key = None;
+	let mut upgrade = false;
+
+	for _ in 0..MAX_HEADER_COUNT {
+		let mut line = String::new();
+		if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+			break;
+		}
+		let mut kv = line.splitn(2, ':');
+		let (name, value) = (kv.next().unwrap_or("").trim().to_lowercase(), kv.next().unwrap_or("").trim());
+		match name.as_str() {
+			"upgrade" => upgrade = value.eq_ignore_ascii_case("websocket"),
+			"sec-websocket-key" => ws_key = Some(value.to_owned()),
+			_ => {},
+		}
+	}
+
+	let mut parts = request_line.split_whitespace();
+	let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+	let mut target = target.splitn(2, '?');
+	let (path, query) = (target.next().unwrap_or(""), target.next().unwrap_or(""));
+
+	if method != "GET" || path != STREAM_PATH {
+		return write_error(&mut stream, "404 Not Found", "not found");
+	}
+
+	let cond = match query::parse_query(query) {
+		Ok(c) => c,
+		Err(e) => return write_error(&mut stream, "400 Bad Request", &e),
+	};
+
+	let protocol = match (upgrade, ws_key) {
+		(true, Some(key)) => {
+			write!(stream, "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\
+				Connection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n", websocket::accept_key(&key))?;
+			Protocol::WebSocket
+		},
+		(true, None) => return write_error(&mut stream, "400 Bad Request", "missing Sec-WebSocket-Key"),
+		(false, _) => {
+			write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\
+				Cache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n")?;
+			Protocol::ServerSentEvents
+		},
+	};
+
+	stream.flush()?;
+	// the reader thread waits for frames from the client as long as the connection is open
+	stream.set_read_timeout(None)?;
+
+	let (tx, rx) = sync_channel(CLIENT_QUEUE_SIZE);
+
+	{
+		let stream = stream.try_clone()?;
+		spawn(move || write_messages(stream, protocol, rx));
+	}
+
+	if protocol == Protocol::WebSocket {
+		let stream = stream.try_clone()?;
+		let tx = tx.clone();
+		spawn(move || read_frames(stream, tx));
+	}
+
+	let mut client = StreamClient {
+		stream: stream,
+		tx: tx,
+		cond: cond,
+		sent: IndexedLimitedQueue::new(SENT_BUFFER_SIZE),
+	};
+
+	// holding the lock so that no EEW is emitted between the replay and the registration;
+	// messages are only queued here, so this never blocks on the network
+	let mut state = state.lock().unwrap();
+
+	for event in state.active.values().filter(|e| is_active(e, active_duration)) {
+		if ! client.offer(&event.eew) {
+			return Ok(());
+		}
+	}
+
+	info!("EEWStream: client connected ({:?})", protocol);
+	state.clients.push(client);
+
+	Ok(())
+}
+
+impl EEWStream {
+
+	pub fn start(listen_addr: &str, active_duration: Duration) -> io::Result<EEWStream>
+	{
+		let listener = TcpListener::bind(listen_addr)?;
+		let addr = listener.local_addr()?;
+
+		let state = Arc::new(Mutex::new(StreamState {
+			clients: Vec::new(),
+			active: IndexedLimitedQueue::new(ACTIVE_EVENT_CAPACITY),
+		}));
+
+		let closed = Arc::new(AtomicBool::new(false));
+
+		let listener = {
+			let state = state.clone();
+			let closed = closed.clone();
+			spawn(move || {
+				for stream in listener.incoming().filter_map(|s| s.ok()) {
+					if closed.load(Ordering::SeqCst) {
+						break;
+					}
+					let state = state.clone();
+					spawn(move || {
+						if let Err(e) = accept(stream, state, active_duration) {
+							debug!("EEWStream: connection error ({})", e);
+						}
+					});
+				}
+			})
+		};
+
+		Ok(EEWStream { addr: addr, state: state, closed: closed, listener: Some(listener) })
+	}
+
+	pub fn local_addr(&self) -> SocketAddr
+	{
+		self.addr
+	}
+}
+
+impl Destination for EEWStream {
+
+	const WAKE_TIMEOUT_SECS: u64 = 15;
+
+	fn emit(&mut self, latest: &EEW, _prev: Option<&EEW>)
+	{
+		let mut state = self.state.lock().unwrap();
+
+		let event = ActiveEvent { eew: latest.clone(), received_at: Instant::now() };
+		state.active.upsert(latest.id.clone(), event);
+
+		state.clients.retain_mut(|c| c.offer(latest));
+	}
+
+	fn wake(&mut self)
+	{
+		self.state.lock().unwrap().clients.retain(|c| c.send(Outgoing::KeepAlive));
+	}
+}
+
+impl Drop for EEWStream {
+
+	// stops listening so that the address can be reused (e.g. after reloading the config)
+	fn drop(&mut self)
+	{
+		self.closed.store(true, Ordering::SeqCst);
+		// wakes up the listener thread blocked in accept and waits for it to close the listener
+		let woken = TcpStream::connect_timeout(&self.addr, Duration::from_secs(NETWORK_TIMEOUT_SECS)).is_ok();
+		if let (true, Some(listener)) = (woken, self.listener.take()) {
+			let _ = listener.join();
+		}
+		self.state.lock().unwrap().clients.clear();
+	}
+}
@@ -0,0 +1,84 @@
+use std::str::FromStr;
+
+use eew::{IntensityClass, WaveStatus};
+use condition::ValueCondition;
+
+fn parse_hex(c: u8) -> Option<u8>
+{
+	(c as char).to_digit(16).map(|d| d as u8)
+}
+
+fn percent_decode(s: &str) -> Option<String>
+{
+	let bytes = s.as_bytes();
+	let mut out = Vec::with_capacity(bytes.len());
+	let mut i = 0;
+
+	while i < bytes.len() {
+		match bytes[i] {
+			b'%' if i + 2 < bytes.len() => {
+				out.push(parse_hex(bytes[i + 1])? * 16 + parse_hex(bytes[i + 2])?);
+				i += 3;
+			},
+			b'%' => return None,
+			b'+' => { out.push(b' '); i += 1; },
+			c => { out.push(c); i += 1; },
+		}
+	}
+
+	String::from_utf8(out).ok()
+}
+
+fn parse<T: FromStr>(value: &str) -> Result<Option<T>, String>
+{
+	value.parse().map(Some).map_err(|_| format!("invalid value: {}", value))
+}
+
+fn parse_wave_status(value: &str) -> Result<Option<WaveStatus>, String>
+{
+	match value {
+		"unreached" => Ok(Some(WaveStatus::Unreached)),
+		"reached" => Ok(Some(WaveStatus::Reached)),
+		"plum" => Ok(Some(WaveStatus::Plum)),
+		"unknown" => Ok(Some(WaveStatus::Unknown)),
+		_ => Err(format!("invalid wave status: {}", value)),
+	}
+}
+
+// builds a condition from query parameters with the same keys as `cond` in the config;
+// like the config, drills and tests are excluded unless specified
+pub fn parse_query(query: &str) -> Result<ValueCondition, String>
+{
+	let mut cond = ValueCondition { drill: Some(false), test: Some(false), .. ValueCondition::default() };
+
+	for pair in query.split('&').filter(|p| ! p.is_empty()) {
+
+		let mut kv = pair.splitn(2, '=');
+		let key = kv.next().unwrap_or("");
+		let value = percent_decode(kv.next().unwrap_or("true"))
+			.ok_or_else(|| format!("invalid encoding: {}", pair))?;
+
+		match key {
+			"first" => cond.first = parse(&value)?,
+			"succeeding" => cond.succeeding = parse(&value)?,
+			"alert" => cond.alert = parse(&value)?,
+			"last" => cond.last = parse(&value)?,
+			"cancel" => cond.cancel = parse(&value)?,
+			"drill" => cond.drill = parse(&value)?,
+			"test" => cond.test = parse(&value)?,
+			"phase_changed" => cond.phase_changed = parse(&value)?,
+			"epicenter_name_changed" => cond.epicenter_name_changed = parse(&value)?,
+			"magnitude_over" => cond.magnitude_over = parse(&value)?,
+			"intensity_over" => cond.intensity_over = parse(&value)?.map(IntensityClass::new),
+			"intensity_up" => cond.intensity_up = parse(&value)?,
+			"intensity_down" => cond.intensity_down = parse(&value)?,
+			"areas" => cond.areas = Some(value.split(',').map(|s| s.to_owned()).collect()),
+			"area_intensity_over" => cond.area_intensity_over = parse(&value)?.map(IntensityClass::new),
+			"area_alert" => cond.area_alert = parse(&value)?,
+			"area_wave_status" => cond.area_wave_status = parse_wave_status(&value)?,
+			_ => return Err(format!("unknown parameter: {}", key)),
+		}
+	}
+
+	Ok(cond)
+}
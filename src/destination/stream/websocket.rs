@@ -0,0 +1,93 @@
+// the minimum of RFC 6455 needed to push messages to clients
+
+use std::io;
+use std::io::Read;
+
+use base64;
+use sha1_smol::Sha1;
+
+const GUID: &'static str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+// clients only send control frames, so longer frames are rejected
+const MAX_PAYLOAD_LEN: u64 = 0xFFFF;
+
+pub const OPCODE_TEXT: u8 = 0x1;
+pub const OPCODE_CLOSE: u8 = 0x8;
+pub const OPCODE_PING: u8 = 0x9;
+pub const OPCODE_PONG: u8 = 0xA;
+const FIN: u8 = 0x80;
+const MASKED: u8 = 0x80;
+
+// the value of `Sec-WebSocket-Accept` for `Sec-WebSocket-Key`
+pub fn accept_key(key: &str) -> String
+{
+	base64::encode(&Sha1::from(format!("{}{}", key.trim(), GUID)).digest().bytes())
+}
+
+pub fn frame(opcode: u8, payload: &[u8]) -> Vec<u8>
+{
+	let mut out = vec![FIN | opcode];
+	let len = payload.len();
+
+	if len < 126 {
+		out.push(len as u8);
+	} else if len <= 0xFFFF {
+		out.push(126);
+		out.extend_from_slice(&(len as u16).to_be_bytes());
+	} else {
+		out.push(127);
+		out.extend_from_slice(&(len as u64).to_be_bytes());
+	}
+
+	out.extend_from_slice(payload);
+	out
+}
+
+pub fn text_frame(text: &str) -> Vec<u8>
+{
+	frame(OPCODE_TEXT, text.as_bytes())
+}
+
+pub fn ping_frame() -> Vec<u8>
+{
+	frame(OPCODE_PING, b"")
+}
+
+// reads a frame sent by a client and returns its opcode and unmasked payload
+pub fn read_frame<R: Read>(reader: &mut R) -> io::Result<(u8, Vec<u8>)>
+{
+	let mut head = [0; 2];
+	reader.read_exact(&mut head)?;
+
+	let opcode = head[0] & 0x0F;
+	let len = match head[1] & 0x7F {
+		126 => {
+			let mut b = [0; 2];
+			reader.read_exact(&mut b)?;
+			u16::from_be_bytes(b) as u64
+		},
+		127 => {
+			let mut b = [0; 8];
+			reader.read_exact(&mut b)?;
+			u64::from_be_bytes(b)
+		},
+		n => n as u64,
+	};
+
+	if len > MAX_PAYLOAD_LEN {
+		return Err(io::Error::new(io::ErrorKind::InvalidData, "too long frame"));
+	}
+
+	let mut mask = [0; 4];
+	if head[1] & MASKED != 0 {
+		reader.read_exact(&mut mask)?;
+	}
+
+	let mut payload = vec![0; len as usize];
+	reader.read_exact(&mut payload)?;
+
+	for (i, b) in payload.iter_mut().enumerate() {
+		*b ^= mask[i % 4];
+	}
+
+	Ok((opcode, payload))
+}
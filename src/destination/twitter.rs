@@ -1,26 +1,30 @@
+use serde_json::Value;
+
 use eew::EEW;
 use destination::client::TwitterClient;
 use destination::Destination;
 use collections::IndexedLimitedQueue;
-use translator::ja_format_eew_oneline;
+use translator::Translator;
 
 pub struct Twitter {
 	client: TwitterClient,
 	latest_tw_ids: IndexedLimitedQueue<u64>,
 	reply_enabled: bool,
 	updown_enabled: bool,
+	translator: Translator,
 }
 
 impl Twitter {
 
 	pub fn new(consumer_key: String, consumer_secret: String,
-		access_key: String, access_secret: String, reply_enabled: bool, updown_enabled: bool) -> Twitter
+		access_key: String, access_secret: String, reply_enabled: bool, updown_enabled: bool,
+		translator: Translator) -> Twitter
 	{
 		let client = TwitterClient::new(consumer_key, consumer_secret, access_key, access_secret);
 		let q = IndexedLimitedQueue::new(16);
 
 		Twitter { client: client, latest_tw_ids: q,
-			reply_enabled: reply_enabled, updown_enabled: updown_enabled }
+			reply_enabled: reply_enabled, updown_enabled: updown_enabled, translator: translator }
 	}
 
 	pub fn is_valid(&self) -> bool
@@ -38,7 +42,7 @@ impl Destination for Twitter {
 			false => None,
 		};
 
-		let out = match ja_format_eew_oneline(latest, prev) {
+		let out = match self.translator.format_eew_oneline(latest, prev) {
 			Some(out) => out,
 			None => return
 		};
@@ -59,4 +63,16 @@ impl Destination for Twitter {
 			}
 		}
 	}
+
+	fn state(&self, id: &str) -> Option<Value>
+	{
+		self.latest_tw_ids.get(id).map(|tw_id| json!(tw_id))
+	}
+
+	fn restore_state(&mut self, id: &str, state: Value)
+	{
+		if let Some(tw_id) = state.as_u64() {
+			self.latest_tw_ids.upsert(id.to_owned(), tw_id);
+		}
+	}
 }
@@ -0,0 +1,48 @@
+use std::time::Duration;
+use std::collections::HashMap;
+
+use eew::EEW;
+use destination::client::WebhookClient;
+use destination::Destination;
+use moderator::Moderator;
+use translator::format_eew_json;
+
+const RETRY_MAX_COUNT: u32 = 4;
+const RETRY_RATE: u32 = 2;
+
+pub struct Webhook {
+	client: WebhookClient,
+	retry_count: u32,
+}
+
+impl Webhook {
+
+	pub fn build(url: &str, headers: &HashMap<String, String>, timeout: Duration, retry_count: u32)
+		-> Result<Webhook, ()>
+	{
+		let client = WebhookClient::build(url, headers, timeout)?;
+		Ok(Webhook { client: client, retry_count: retry_count })
+	}
+}
+
+impl Destination for Webhook {
+
+	fn emit(&mut self, latest: &EEW, prev: Option<&EEW>)
+	{
+		let payload = format_eew_json(latest, prev);
+		let mut moderator = Moderator::with_custom_rate(RETRY_MAX_COUNT, RETRY_RATE);
+
+		for trial in 0..(self.retry_count + 1) {
+
+			if trial > 0 {
+				moderator.wait_for_retry();
+				moderator.add_count();
+			}
+
+			match self.client.post(&payload) {
+				Ok(_) => return,
+				Err(e) => error!("WebhookError: {:?} (trial {})", e, trial + 1),
+			}
+		}
+	}
+}
@@ -1,48 +1,75 @@
 use chrono::{DateTime, Utc};
 
+use eew::eew_serde::lat_lon;
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum IssuePattern { Cancel, IntensityOnly, LowAccuracy, HighAccuracy }
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Source { Tokyo, Osaka }
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Kind { Normal, Drill, Cancel, DrillCancel, Reference, Trial }
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Status { Normal, Correction, CancelCorrection, LastWithCorrection, Last, Unknown }
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum EpicenterAccuracy {
 	Single, Territory, GridSearchLow, GridSearchHigh,
-	NIEDLow, NIEDHigh, EPOSLow, EPOSHigh, Unknown
+	#[serde(rename = "nied_low")] NIEDLow,
+	#[serde(rename = "nied_high")] NIEDHigh,
+	#[serde(rename = "epos_low")] EPOSLow,
+	#[serde(rename = "epos_high")] EPOSHigh,
+	Unknown
 }
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DepthAccuracy {
 	Single, Territory, GridSearchLow, GridSearchHigh,
-	NIEDLow, NIEDHigh, EPOSLow, EPOSHigh, Unknown
+	#[serde(rename = "nied_low")] NIEDLow,
+	#[serde(rename = "nied_high")] NIEDHigh,
+	#[serde(rename = "epos_low")] EPOSLow,
+	#[serde(rename = "epos_high")] EPOSHigh,
+	Unknown
 }
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MagnitudeAccuracy {
-	NIED, PWave, PSMixed, SWave, EPOS, Level, Unknown
+	#[serde(rename = "nied")] NIED,
+	PWave,
+	#[serde(rename = "ps_mixed")] PSMixed,
+	SWave,
+	#[serde(rename = "epos")] EPOS,
+	Level, Unknown
 }
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum EpicenterCategory { Land, Sea, Unknown }
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum WarningStatus { Forecast, Alert, Unknown }
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum IntensityChange { Same, Up, Down, Unknown }
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ChangeReason { Nothing, Magnitude, Epicenter, Mixed, Depth, Plum, Unknown }
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum WaveStatus { Unreached, Reached, Plum, Unknown }
 
 #[derive(Ord, PartialOrd, Eq, PartialEq, Debug, Clone, Copy)]
@@ -67,11 +94,45 @@ impl IntensityClass {
 			_ => IntensityClass::Seven,
 		}
 	}
+
+	pub fn as_jma_str(&self) -> &'static str
+	{
+		match *self {
+			IntensityClass::Zero => "0",
+			IntensityClass::One => "1",
+			IntensityClass::Two => "2",
+			IntensityClass::Three => "3",
+			IntensityClass::Four => "4",
+			IntensityClass::FiveLower => "5-",
+			IntensityClass::FiveUpper => "5+",
+			IntensityClass::SixLower => "6-",
+			IntensityClass::SixUpper => "6+",
+			IntensityClass::Seven => "7",
+		}
+	}
+
+	pub fn from_jma_str(s: &str) -> Option<IntensityClass>
+	{
+		match s {
+			"0" => Some(IntensityClass::Zero),
+			"1" => Some(IntensityClass::One),
+			"2" => Some(IntensityClass::Two),
+			"3" => Some(IntensityClass::Three),
+			"4" => Some(IntensityClass::Four),
+			"5-" => Some(IntensityClass::FiveLower),
+			"5+" => Some(IntensityClass::FiveUpper),
+			"6-" => Some(IntensityClass::SixLower),
+			"6+" => Some(IntensityClass::SixUpper),
+			"7" => Some(IntensityClass::Seven),
+			_ => None,
+		}
+	}
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
 pub struct AreaEEW {
 
+	#[serde(default)] pub area_code: String,
 	pub area_name: String,
 	pub minimum_intensity: IntensityClass,
 	pub maximum_intensity: Option<IntensityClass>,
@@ -80,7 +141,7 @@ pub struct AreaEEW {
 	pub wave_status: WaveStatus,
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
 pub struct EEW {
 
 	pub issue_pattern: IssuePattern,
@@ -96,11 +157,12 @@ pub struct EEW {
 	pub detail: Option<EEWDetail>,
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
 pub struct EEWDetail {
 
+	#[serde(default)] pub epicenter_code: String,
 	pub epicenter_name: String,
-	pub epicenter: (f32, f32),
+	#[serde(with = "lat_lon")] pub epicenter: (f32, f32),
 	pub depth: Option<f32>,
 	pub magnitude: Option<f32>,
 	pub maximum_intensity: Option<IntensityClass>,
@@ -1,6 +1,7 @@
 use eew::*;
 
-#[derive(Ord, PartialOrd, Eq, PartialEq, Debug, Clone, Copy)]
+#[derive(Ord, PartialOrd, Eq, PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum EEWPhase {
 	Cancel,
 	FastForecast,
@@ -16,6 +17,9 @@ impl EEW {
 
 			Kind::Cancel | Kind::DrillCancel => Some(EEWPhase::Cancel),
 
+			// test telegrams have no dedicated kind for cancellation
+			Kind::Reference | Kind::Trial if self.issue_pattern == IssuePattern::Cancel => Some(EEWPhase::Cancel),
+
 			Kind::Normal | Kind::Drill | Kind::Reference | Kind::Trial => {
 
 				match self.detail.as_ref().map(|d| d.warning_status) {
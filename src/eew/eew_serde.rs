@@ -0,0 +1,116 @@
+use std::fmt;
+
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde::de::{Error as DeError, Visitor};
+use serde_json;
+
+use eew::{EEW, IntensityClass};
+
+
+pub const EEW_JSON_VERSION: u32 = 1;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum EEWJsonError {
+	InvalidFormat,
+	UnsupportedVersion(u32),
+}
+
+#[derive(Serialize)]
+struct VersionedEEWRef<'a> {
+	version: u32,
+	eew: &'a EEW,
+}
+
+#[derive(Deserialize)]
+struct VersionedHeader {
+	version: u32,
+}
+
+#[derive(Deserialize)]
+struct VersionedEEW {
+	eew: EEW,
+}
+
+impl EEW {
+
+	pub fn to_json(&self) -> String
+	{
+		let versioned = VersionedEEWRef { version: EEW_JSON_VERSION, eew: self };
+		serde_json::to_string(&versioned).expect("EEW is always serializable")
+	}
+
+	pub fn from_json(text: &str) -> Result<EEW, EEWJsonError>
+	{
+		let header: VersionedHeader =
+			serde_json::from_str(text).map_err(|_| EEWJsonError::InvalidFormat)?;
+
+		if header.version != EEW_JSON_VERSION {
+			return Err(EEWJsonError::UnsupportedVersion(header.version));
+		}
+
+		let versioned: VersionedEEW =
+			serde_json::from_str(text).map_err(|_| EEWJsonError::InvalidFormat)?;
+
+		Ok(versioned.eew)
+	}
+}
+
+impl Serialize for IntensityClass {
+
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where S: Serializer
+	{
+		serializer.serialize_str(self.as_jma_str())
+	}
+}
+
+struct IntensityClassVisitor;
+
+impl<'d> Visitor<'d> for IntensityClassVisitor {
+
+	type Value = IntensityClass;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result
+	{
+		formatter.write_str("a JMA intensity string such as \"5-\"")
+	}
+
+	fn visit_str<E>(self, v: &str) -> Result<IntensityClass, E>
+		where E: DeError
+	{
+		IntensityClass::from_jma_str(v).ok_or_else(|| E::custom(format!("unknown intensity: {}", v)))
+	}
+}
+
+impl<'d> Deserialize<'d> for IntensityClass {
+
+	fn deserialize<D>(deserializer: D) -> Result<IntensityClass, D::Error>
+		where D: Deserializer<'d>
+	{
+		deserializer.deserialize_str(IntensityClassVisitor)
+	}
+}
+
+// (lat, lon) tuples are represented as `{ "lat": ..., "lon": ... }`
+pub mod lat_lon {
+
+	use serde::{Serialize, Serializer, Deserialize, Deserializer};
+
+	#[derive(Serialize, Deserialize)]
+	struct LatLon {
+		lat: f32,
+		lon: f32,
+	}
+
+	pub fn serialize<S>(pos: &(f32, f32), serializer: S) -> Result<S::Ok, S::Error>
+		where S: Serializer
+	{
+		LatLon { lat: pos.0, lon: pos.1 }.serialize(serializer)
+	}
+
+	pub fn deserialize<'d, D>(deserializer: D) -> Result<(f32, f32), D::Error>
+		where D: Deserializer<'d>
+	{
+		LatLon::deserialize(deserializer).map(|v| (v.lat, v.lon))
+	}
+}
@@ -1,5 +1,8 @@
 mod eew;
 mod eew_extension;
+mod eew_serde;
 
 pub use self::eew::*;
 pub use self::eew_extension::*;
+pub use self::eew_serde::{EEWJsonError, EEW_JSON_VERSION};
+pub(crate) use self::eew_serde::lat_lon;
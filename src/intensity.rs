@@ -0,0 +1,66 @@
+use eew::{EEW, IntensityClass};
+use observer::epicentral_distance_km;
+
+// Mj of EEWs is converted to Mw with a constant offset;
+// the attenuation relation is not valid beyond Mw 8.3, where ground motions saturate
+const MJ_TO_MW_OFFSET: f32 = 0.171;
+const MAX_MOMENT_MAGNITUDE: f32 = 8.3;
+const MIN_FAULT_DISTANCE_KM: f32 = 3.0;
+
+pub fn moment_magnitude(mj: f32) -> f32
+{
+	(mj - MJ_TO_MW_OFFSET).min(MAX_MOMENT_MAGNITUDE)
+}
+
+// the fault length by Utsu's empirical relation, in km
+fn fault_length_km(mw: f32) -> f32
+{
+	10f32.powf(0.5 * mw - 1.85)
+}
+
+// the peak ground velocity on the engineering bedrock (Vs = 600m/s) in cm/s,
+// by the attenuation relation of Si and Midorikawa (1999) for crustal earthquakes;
+// the distance to the fault is approximated by the hypocentral distance minus a half of the fault length
+pub fn bedrock_pgv(mj: f32, depth_km: f32, hypocentral_distance_km: f32) -> f32
+{
+	let mw = moment_magnitude(mj);
+	let x = (hypocentral_distance_km - fault_length_km(mw) / 2.0).max(MIN_FAULT_DISTANCE_KM);
+
+	let log_pgv = 0.58 * mw + 0.0038 * depth_km - 1.29
+		- (x + 0.0028 * 10f32.powf(0.5 * mw)).log10() - 0.002 * x;
+
+	10f32.powf(log_pgv)
+}
+
+// the amplification of the surface PGV relative to the engineering bedrock
+// from AVS30 (the average S-wave velocity of the top 30m in m/s) by Fujimoto and Midorikawa (2006)
+pub fn site_amplification(avs30: f32) -> f32
+{
+	10f32.powf(2.367 - 0.852 * avs30.log10())
+}
+
+// the JMA instrumental intensity from the surface PGV by Midorikawa et al. (1999)
+pub fn instrumental_intensity(pgv: f32) -> f32
+{
+	2.68 + 1.72 * pgv.log10()
+}
+
+// the estimated instrumental intensity at `pos` (latitude, longitude) with the site amplification factor;
+// None for EEWs without the magnitude (e.g. cancels and intensity-only EEWs)
+pub fn estimate_intensity(eew: &EEW, pos: (f32, f32), amplification: f32) -> Option<f32>
+{
+	let detail = eew.detail.as_ref()?;
+	let magnitude = detail.magnitude?;
+
+	let depth = detail.depth.unwrap_or(0.0);
+	let epicentral = epicentral_distance_km(detail.epicenter, pos);
+	let distance = (epicentral * epicentral + depth * depth).sqrt();
+
+	let pgv = bedrock_pgv(magnitude, depth, distance) * amplification;
+	Some(instrumental_intensity(pgv))
+}
+
+pub fn estimate_intensity_class(eew: &EEW, pos: (f32, f32), amplification: f32) -> Option<IntensityClass>
+{
+	estimate_intensity(eew, pos, amplification).map(IntensityClass::new)
+}
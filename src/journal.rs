@@ -0,0 +1,223 @@
+use std::io;
+use std::io::{BufRead, BufReader, Write};
+use std::fs::{File, OpenOptions, rename};
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+use std::thread::JoinHandle;
+
+use chrono::{DateTime, Utc, Duration};
+use serde_json;
+use serde_json::Value;
+
+use eew::EEW;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+enum Record {
+	Eew(EEW),
+	State { destination: String, id: String, value: Value },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Entry {
+	recorded_at: DateTime<Utc>,
+	#[serde(flatten)] record: Record,
+}
+
+struct Entries {
+	retention: Duration,
+	entries: Vec<Entry>,
+	// None if the entries are kept only in memory
+	writer: Option<Sender<FileOp>>,
+}
+
+// the writes to the file, which are done on the writer thread so that the callers never wait for the disk
+enum FileOp {
+	Append(Entry),
+	// rewrites the file with the retained entries only
+	Compact(Vec<Entry>),
+}
+
+struct Shared {
+	entries: Mutex<Entries>,
+	writer_thread: Option<JoinHandle<()>>,
+}
+
+// an append-only journal (JSON lines) of accepted EEWs and destination states,
+// which survives restarts; entries older than the retention window are dropped
+#[derive(Clone)]
+pub struct Journal {
+	inner: Arc<Shared>,
+}
+
+fn read_entries(path: &str) -> io::Result<Vec<Entry>>
+{
+	let file = match File::open(path) {
+		Ok(f) => f,
+		Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+		Err(e) => return Err(e),
+	};
+
+	let mut entries = Vec::new();
+
+	for (i, line) in BufReader::new(file).lines().enumerate() {
+		let line = line?;
+		if line.trim().is_empty() {
+			continue;
+		}
+		match serde_json::from_str(&line) {
+			Ok(e) => entries.push(e),
+			// a line may be truncated when the process is killed while writing
+			Err(e) => warn!("Journal: skipping an invalid entry at {}:{} ({})", path, i + 1, e),
+		}
+	}
+
+	Ok(entries)
+}
+
+fn write_entries(file: &mut File, entries: &[Entry]) -> io::Result<()>
+{
+	for entry in entries.iter() {
+		let line = serde_json::to_string(entry).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+		writeln!(file, "{}", line)?;
+	}
+	file.flush()
+}
+
+fn compact(path: &str, entries: &[Entry]) -> io::Result<File>
+{
+	let tmp_path = format!("{}.tmp", path);
+
+	{
+		let mut tmp = File::create(&tmp_path)?;
+		write_entries(&mut tmp, entries)?;
+		tmp.sync_all()?;
+	}
+
+	rename(&tmp_path, path)?;
+	OpenOptions::new().append(true).create(true).open(path)
+}
+
+fn run_writer(path: String, file: File, rx: Receiver<FileOp>)
+{
+	let mut file = file;
+
+	for op in rx {
+		let result = match op {
+			FileOp::Append(entry) => write_entries(&mut file, &[entry]),
+			FileOp::Compact(entries) => compact(&path, &entries).map(|f| file = f),
+		};
+
+		if let Err(e) = result {
+			error!("Journal: failed to write to {} ({})", path, e);
+		}
+	}
+}
+
+impl Entries {
+
+	// returns whether any entry is dropped
+	fn drop_expired(&mut self, now: DateTime<Utc>) -> bool
+	{
+		let retention = self.retention;
+		let before = self.entries.len();
+		self.entries.retain(|e| e.recorded_at + retention >= now);
+		self.entries.len() < before
+	}
+
+	fn append(&mut self, record: Record)
+	{
+		let now = Utc::now();
+		let entry = Entry { recorded_at: now, record: record };
+
+		let expired = self.drop_expired(now);
+		self.entries.push(entry.clone());
+
+		// sent while locked to keep the order of the records
+		if let Some(ref tx) = self.writer {
+			let op = match expired {
+				true => FileOp::Compact(self.entries.clone()),
+				false => FileOp::Append(entry),
+			};
+			let _ = tx.send(op);
+		}
+	}
+}
+
+impl Drop for Shared {
+
+	// writes the rest of the entries before the journal is gone (e.g. on restarts in tests)
+	fn drop(&mut self)
+	{
+		if let Ok(entries) = self.entries.get_mut() {
+			entries.writer = None;
+		}
+		if let Some(handle) = self.writer_thread.take() {
+			let _ = handle.join();
+		}
+	}
+}
+
+impl Journal {
+
+	pub fn open(path: &str, retention: Duration) -> io::Result<Journal>
+	{
+		let mut entries = Entries { retention: retention, entries: read_entries(path)?, writer: None };
+		entries.drop_expired(Utc::now());
+		let file = compact(path, &entries.entries)?;
+
+		let (tx, rx) = channel();
+		let writer_path = path.to_owned();
+		let handle = thread::Builder::new().name("journal".to_owned())
+			.spawn(move || run_writer(writer_path, file, rx))?;
+		entries.writer = Some(tx);
+
+		Ok(Journal { inner: Arc::new(Shared { entries: Mutex::new(entries), writer_thread: Some(handle) }) })
+	}
+
+	// a journal which does not survive restarts but keeps the states over reloads of the config
+	pub fn in_memory(retention: Duration) -> Journal
+	{
+		let entries = Entries { retention: retention, entries: Vec::new(), writer: None };
+		Journal { inner: Arc::new(Shared { entries: Mutex::new(entries), writer_thread: None }) }
+	}
+
+	pub fn record_eew(&self, eew: &EEW)
+	{
+		self.inner.entries.lock().unwrap().append(Record::Eew(eew.clone()));
+	}
+
+	// records the state of `destination` for the event `id` unless it is unchanged
+	pub fn record_state(&self, destination: &str, id: &str, value: Value)
+	{
+		let mut inner = self.inner.entries.lock().unwrap();
+
+		let unchanged = inner.entries.iter().rev().filter_map(|e| match e.record {
+			Record::State { destination: ref d, id: ref i, value: ref v } if d == destination && i == id => Some(v),
+			_ => None,
+		}).next() == Some(&value);
+
+		if ! unchanged {
+			inner.append(Record::State { destination: destination.to_owned(), id: id.to_owned(), value: value });
+		}
+	}
+
+	// the retained EEWs in the order of records
+	pub fn eews(&self) -> Vec<EEW>
+	{
+		self.inner.entries.lock().unwrap().entries.iter().filter_map(|e| match e.record {
+			Record::Eew(ref eew) => Some(eew.clone()),
+			_ => None,
+		}).collect()
+	}
+
+	// the retained states of `destination` as (event ID, state) in the order of records
+	pub fn states(&self, destination: &str) -> Vec<(String, Value)>
+	{
+		self.inner.entries.lock().unwrap().entries.iter().filter_map(|e| match e.record {
+			Record::State { destination: ref d, ref id, ref value } if d == destination => Some((id.clone(), value.clone())),
+			_ => None,
+		}).collect()
+	}
+}
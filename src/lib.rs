@@ -1,4 +1,6 @@
 extern crate oauthcli;
+extern crate serde;
+#[macro_use] extern crate serde_derive;
 #[macro_use] extern crate serde_json;
 extern crate chrono;
 extern crate md5;
@@ -6,6 +8,11 @@ extern crate rand;
 #[macro_use] extern crate slog;
 #[macro_use] extern crate slog_scope;
 extern crate reqwest;
+extern crate handlebars;
+extern crate serde_yaml;
+extern crate csv;
+extern crate base64;
+extern crate sha1_smol;
 
 macro_rules! write_unwrap {
 	($dst:expr, $($arg:tt)*) => (write!($dst, $($arg)*).unwrap())
@@ -19,13 +26,39 @@ mod destination;
 mod translator;
 mod moderator;
 mod condition;
+mod api;
+mod metrics;
+mod journal;
+mod observer;
+mod intensity;
+mod travel_time;
+mod config;
+mod check;
+mod routers;
 
 pub use self::collections::*;
 pub use self::eew::*;
 pub use self::parser::*;
-pub use self::source::Wni;
-pub use self::destination::{Twitter, Logging, Slack, Router, Routing};
-pub use self::translator::{ja_format_eew_oneline, format_eew_full};
+pub use self::source::{Wni, WniConnection, WniError, TelegramAssembler, Replay, extract_telegrams};
+pub use self::source::{synthetic_eews, spawn_conn_thread};
+#[cfg(feature = "fake-wni")] pub use self::source::{FakeWniServer, FakeWniEvent, FakeWniStats};
+pub use self::destination::{Twitter, Logging, Slack, PushBullet, Discord, Mqtt, EEWStream, Webhook, Exec, Router, Routing,
+	RouteResult, Destination};
+pub use self::destination::{SlackThread, SlackBotAction, SlackMessage, SlackError, PushBulletError};
+pub use self::translator::{ja_format_eew_oneline, en_format_eew_oneline, format_eew_full, format_eew_json,
+	Language, NameDict, Translator, Template, TemplateError};
 pub use self::moderator::Moderator;
+pub use self::metrics::Metrics;
+pub use self::journal::Journal;
+pub use self::observer::{Observer, Arrival, epicentral_distance_km};
+pub use self::travel_time::TravelTimeTable;
+pub use self::intensity::{moment_magnitude, bedrock_pgv, site_amplification, instrumental_intensity,
+	estimate_intensity, estimate_intensity_class};
+pub use self::config::{Config, ConfigLoadError, WniConfig, LogConfig, HttpConfig, JournalConfig,
+	TwitterConfig, SlackConfig, PushBulletConfig, DiscordConfig, MqttConfig, StreamConfig, WebhookConfig, ExecConfig,
+	ConditionConfig, ValueConditionConfig, build_yaml_condition};
+pub use self::check::{CheckReport, check_config};
+pub use self::routers::{build_routers, reload_routers};
+pub use self::api::{TinaState, ConnectionStatus, HttpApi};
 pub use self::condition::{Condition, ConstantCondition, TRUE_CONDITION, FALSE_CONDITION,
-	DisjunctiveCondition, ValueCondition};
+	DisjunctiveCondition, ConjunctiveCondition, NegatedCondition, ValueCondition};
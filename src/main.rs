@@ -1,36 +1,41 @@
-#[macro_use] extern crate serde_derive;
-extern crate serde;
-extern crate serde_yaml;
-extern crate csv;
 extern crate tina;
 #[macro_use] extern crate slog;
 #[macro_use] extern crate slog_scope;
 extern crate slog_term;
-
-mod config;
+extern crate signal_hook;
+extern crate clap;
+extern crate chrono;
 
 use std::io::stdout;
 use std::env;
+use std::process;
+use std::fs;
 use std::fs::OpenOptions;
-use std::sync::mpsc::sync_channel;
-use std::sync::mpsc::SyncSender;
-use std::thread::{spawn, JoinHandle};
+use std::sync::mpsc::{sync_channel, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::sleep;
 use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
 
-use slog::{Drain, Logger, Discard, Duplicate};
+use slog::{Drain, Logger, Discard, Duplicate, Level};
 use slog_scope::set_global_logger;
 use slog_term::{PlainSyncDecorator, FullFormat};
+use signal_hook::consts::SIGHUP;
+use clap::{App, AppSettings, Arg, SubCommand};
+use chrono::Utc;
 
 use tina::*;
-use config::*;
 
-const REVISION: &'static str = env!("TINA_REVISION");
+const VERSION: &'static str = concat!("rev.", env!("TINA_REVISION"));
 const CONF_PATH_ENV_VAR: &'static str = "TINA_CONF_PATH";
 const DEFAULT_CONFIG_PATH: &'static str = "config/tina.yaml";
 const SERVER_LIST_URL: &'static str = "http://lst10s-sp.wni.co.jp/server_list.txt";
 const WNI_THREAD_COUNT: u32 = 4;
 
 const EEW_HISTORY_CAPACITY: usize = 128;
+const RELOAD_CHECK_INTERVAL_SECS: u64 = 1;
+const MEMORY_JOURNAL_RETENTION_SECS: i64 = 3600;
 
 fn build_specific_logger(log_path: &Option<String>, duplication: bool, default: &Logger) -> Logger
 {
@@ -51,70 +56,354 @@ fn build_specific_logger(log_path: &Option<String>, duplication: bool, default:
 	}
 }
 
-fn spawn_conn_thread(thread_num: u32, wni: Wni,
-	epicenter_dict: HashMap<[u8; 3], String>, area_dict: HashMap<[u8; 3], String>,
-	sock: SyncSender<EEW>) -> JoinHandle<()>
+fn start_http_api(conf: &Config, state: &Arc<Mutex<TinaState>>)
+{
+	if let Some(ref h) = conf.http.as_ref() {
+		match HttpApi::start(&h.listen, state.clone()) {
+			Ok(api) => info!("Enabled: HTTP API ({})", api.local_addr()),
+			Err(e) => error!("HttpApiError: failed to listen on {} ({})", h.listen, e),
+		}
+	}
+}
+
+fn modified_time(path: &str) -> Option<SystemTime>
+{
+	fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn open_journal(conf: &Config) -> Option<Journal>
+{
+	let j = conf.journal.as_ref()?;
+
+	match Journal::open(&j.path, chrono::Duration::seconds(j.retention_secs as i64)) {
+		Ok(journal) => {
+			info!("Enabled: Journal ({})", j.path);
+			Some(journal)
+		},
+		Err(e) => {
+			error!("Journal: failed to open {} ({})", j.path, e);
+			None
+		}
+	}
+}
+
+fn run(conf: &Config, conf_path: &str, wni_logger: Logger, eew_logger: Logger, mut socks: Vec<Box<dyn Routing>>,
+	journal: Journal)
 {
-	spawn(move || {
+	let server_list_url = conf.wni.server_list_url.clone().unwrap_or(SERVER_LIST_URL.to_owned());
+	let wni = Wni::new(conf.wni.id.clone(), "40285072".to_owned(), conf.wni.password.clone(),
+		server_list_url, Some(wni_logger));
+
+	let mut conn_threads = Vec::new();
+	let (eew_tx, eew_rx) = sync_channel(32);
+
+	let mut his = EEWHistory::new(EEW_HISTORY_CAPACITY);
+
+	let eews = journal.eews();
+	if ! eews.is_empty() {
+		info!("Journal: {} EEWs restored", eews.len());
+	}
+	for eew in eews {
+		his.restore(eew);
+	}
+
+	let state = Arc::new(Mutex::new(TinaState::new(his, WNI_THREAD_COUNT as usize)));
+	start_http_api(conf, &state);
+
+	for thread_num in 0..WNI_THREAD_COUNT {
 
-		let mut moderator = Moderator::new();
+		let t = spawn_conn_thread(thread_num, wni.clone(),
+			conf.epicenter_dict.clone(), conf.area_dict.clone(), eew_tx.clone(), state.clone());
+		conn_threads.push(t);
+	}
 
-		loop {
+	let reload_requested = Arc::new(AtomicBool::new(false));
+	if let Err(e) = signal_hook::flag::register(SIGHUP, reload_requested.clone()) {
+		warn!("Reload: failed to register the SIGHUP handler ({})", e);
+	}
 
-			let mut connection = match wni.connect() {
-				Ok(v) => v,
-				Err(e) => {
-					error!("Thread {} - ConnectionError: {:?}", thread_num, e);
-					moderator.wait_for_retry();
-					moderator.add_count();
-					continue;
+	let mut last_modified = modified_time(conf_path);
+	let mut auto_reload = conf.auto_reload;
+
+	loop {
+		match eew_rx.recv_timeout(Duration::from_secs(RELOAD_CHECK_INTERVAL_SECS)) {
+			Ok(eew) => {
+				let appended = state.lock().unwrap().history.append(eew);
+				if let Some(eew) = appended {
+					for s in socks.iter_mut() {
+						s.emit(&eew);
+					}
+					// the file is written on the writer thread of the journal
+					journal.record_eew(&eew);
 				}
-			};
+			},
+			Err(RecvTimeoutError::Timeout) => {},
+			Err(RecvTimeoutError::Disconnected) => unreachable!("senders are held by this function"),
+		}
 
-			moderator.reset();
-			info!("Thread {} - Connected: WNI ({})", thread_num, connection.server());
+		let mut reload = reload_requested.swap(false, Ordering::SeqCst);
 
-			loop {
+		if auto_reload {
+			let modified = modified_time(conf_path);
+			if modified != last_modified {
+				last_modified = modified;
+				reload = true;
+			}
+		}
 
-				let eew = match connection.wait_for_telegram(&epicenter_dict, &area_dict) {
-					Err(e) => {
-						error!("Thread {} - StreamingError: {:?}", thread_num, e);
-						break;
-					},
-					Ok(eew) => eew
-				};
+		if reload {
+			if let Some(c) = reload_routers(conf_path, &mut socks, &eew_logger, &journal) {
+				auto_reload = c.auto_reload;
+			}
+		}
+	}
+}
+
+fn replay(conf: &Config, mut socks: Vec<Box<dyn Routing>>, log_path: &str, speed: Option<f64>)
+{
+	let replay = match Replay::load(log_path, speed) {
+		Err(err) => {
+			error!("Error while loading replay log from '{}' ({})", log_path, err);
+			return;
+		},
+		Ok(r) => r
+	};
 
-				sock.try_send(eew).expect("should not fail");
+	info!("Replay: {} telegrams loaded from '{}'", replay.telegrams().len(), log_path);
+
+	let his = EEWHistory::new(EEW_HISTORY_CAPACITY);
+	let state = Arc::new(Mutex::new(TinaState::new(his, 0)));
+	start_http_api(conf, &state);
+
+	replay.play(&conf.epicenter_dict, &conf.area_dict, |result| {
+		match result {
+			Err(e) => warn!("Replay: ParseError: {:?}", e),
+			Ok(eew) => {
+				let appended = state.lock().unwrap().history.append(eew);
+				if let Some(eew) = appended {
+					for s in socks.iter_mut() {
+						s.emit(&eew);
+					}
+				}
 			}
 		}
-	})
+	});
+
+	// dropping routers waits for all destinations to finish
+	drop(socks);
+	info!("Replay: finished");
 }
 
-fn main()
+// prints the problems found in the config and returns the exit code
+fn check_config_command(conf_path: &str) -> i32
 {
-	let cmd_args: Vec<String> = env::args().collect();
+	let report = check_config(conf_path);
 
-	match cmd_args.get(1).map(|s| s.as_str()) {
-		Some("-v") | Some("--version") => {
-			eprintln!("Tina - EEW Client (rev.{})", REVISION);
-			return;
+	for e in report.errors.iter() {
+		println!("error: {}", e);
+	}
+	for w in report.warnings.iter() {
+		println!("warning: {}", w);
+	}
+
+	println!("{}: {} error(s), {} warning(s)", conf_path, report.errors.len(), report.warnings.len());
+
+	match report.errors.is_empty() {
+		true => 0,
+		false => 1,
+	}
+}
+
+// decodes the telegrams in the file and prints them; returns the exit code
+fn parse_command(conf: &Config, path: &str, json: bool) -> i32
+{
+	let replay = match Replay::load(path, None) {
+		Err(err) => {
+			eprintln!("Error while loading telegrams from '{}' ({})", path, err);
+			return 1;
 		},
-		_ => {}
+		Ok(r) => r
+	};
+
+	let mut prevs: HashMap<String, EEW> = HashMap::new();
+	let mut failed = false;
+
+	replay.play(&conf.epicenter_dict, &conf.area_dict, |result| {
+		match result {
+			Err(e) => {
+				eprintln!("ParseError: {:?}", e);
+				failed = true;
+			},
+			Ok(eew) => {
+				match json {
+					true => println!("{}", format_eew_json(&eew, prevs.get(&eew.id))),
+					false => println!("{}", format_eew_full(&eew)),
+				}
+				prevs.insert(eew.id.clone(), eew);
+			}
+		}
+	});
+
+	match failed {
+		true => 1,
+		false => 0,
 	}
+}
 
-	let conf_path_arg = cmd_args.get(1).map(|s| s.as_str());
+// pushes synthetic EEWs through the router named `dest_name`; returns the exit code,
+// which is non-zero if none of them is sent (e.g. the condition does not allow `test: true`)
+fn send_test_command(conf: &Config, eew_logger: Logger, dest_name: &str, kind: Kind,
+	interval: Duration, bypass_condition: bool) -> i32
+{
+	let mut socks = build_routers(conf, eew_logger, None);
+	let names: Vec<String> = socks.iter().map(|s| s.name().to_owned()).collect();
+
+	let sock = match socks.iter_mut().find(|s| s.name().eq_ignore_ascii_case(dest_name)) {
+		Some(s) => s,
+		None => {
+			eprintln!("Unknown destination: {} (enabled: {})", dest_name, names.join(", "));
+			return 1;
+		}
+	};
+
+	let eews = synthetic_eews(Utc::now(), kind);
+	let mut sent_count = 0;
+
+	for (i, eew) in eews.into_iter().enumerate() {
+
+		if i > 0 {
+			sleep(interval);
+		}
+
+		info!("SendTest: sending {} (#{}) to {}", eew.id, eew.number, sock.name());
+
+		let eew = Arc::new(eew);
+		let result = match bypass_condition {
+			true => sock.emit_unconditionally(&eew),
+			false => sock.emit(&eew),
+		};
+
+		match result {
+			RouteResult::Sent => {
+				sent_count += 1;
+				println!("{} (#{}): sent", eew.id, eew.number);
+			},
+			RouteResult::Filtered => println!("{} (#{}): filtered by the condition", eew.id, eew.number),
+			RouteResult::Failed => println!("{} (#{}): failed", eew.id, eew.number),
+		}
+	}
+
+	// dropping routers waits for all destinations to finish
+	drop(socks);
+
+	match sent_count {
+		0 => {
+			eprintln!("No EEW was sent; the condition of {} has to allow `test: true` (or use --bypass-condition)", dest_name);
+			1
+		},
+		_ => 0,
+	}
+}
+
+fn build_cli() -> App<'static, 'static>
+{
+	let speed_validator = |v: String| match v.parse::<f64>() {
+		Ok(v) if v > 0.0 => Ok(()),
+		_ => Err(format!("invalid speed factor: {}", v)),
+	};
+
+	let interval_validator = |v: String| match v.parse::<u64>() {
+		Ok(_) => Ok(()),
+		_ => Err(format!("invalid interval: {}", v)),
+	};
+
+	App::new("tina")
+		.about("Tina - EEW Client")
+		.setting(AppSettings::VersionlessSubcommands)
+		// handled in `main` to keep the output of the former versions (on stderr)
+		.arg(Arg::with_name("version").long("version").short("v").help("Prints the version"))
+		.arg(Arg::with_name("config").long("config").short("c").global(true).takes_value(true)
+			.value_name("PATH").help("Sets the config file (defaults to $TINA_CONF_PATH or config/tina.yaml)"))
+		.arg(Arg::with_name("log-level").long("log-level").global(true).takes_value(true)
+			.possible_values(&["critical", "error", "warning", "info", "debug"])
+			.help("Overrides `log_level` in the config"))
+		.arg(Arg::with_name("legacy-config").hidden(true))
+		.subcommand(SubCommand::with_name("run")
+			.about("Receives EEWs from WNI and sends them to the destinations (default)"))
+		.subcommand(SubCommand::with_name("check-config")
+			.about("Validates the config and exits with a non-zero status on errors")
+			.arg(Arg::with_name("path").help("The config file to validate (same as --config)")))
+		.subcommand(SubCommand::with_name("parse")
+			.about("Decodes raw JMA telegrams (or a WNI log) and prints them")
+			.arg(Arg::with_name("file").required(true))
+			.arg(Arg::with_name("json").long("json").help("Prints EEWs in JSON")))
+		.subcommand(SubCommand::with_name("replay")
+			.about("Replays telegrams in a WNI log (or raw JMA telegrams) through the destinations")
+			.arg(Arg::with_name("file").required(true))
+			.arg(Arg::with_name("speed").long("speed").takes_value(true).value_name("FACTOR")
+				.validator(speed_validator).help("Keeps the original intervals divided by the factor")))
+		.subcommand(SubCommand::with_name("send-test")
+			.about("Sends synthetic test EEWs (first report, intensity up, alert, last and cancel) through the destination")
+			.after_help("The EEWs are test telegrams, so the condition of the destination has to allow `test: true` \
+				unless --bypass-condition is given; exits with a non-zero status if none of them is sent.")
+			.arg(Arg::with_name("destination").required(true)
+				.help("Name of the destination (e.g. Slack, Twitter, Webhook)"))
+			.arg(Arg::with_name("kind").long("kind").takes_value(true)
+				.possible_values(&["trial", "reference"]).default_value("trial"))
+			.arg(Arg::with_name("interval").long("interval").takes_value(true).value_name("SECS")
+				.default_value("3").validator(interval_validator).help("Interval between the EEWs"))
+			.arg(Arg::with_name("bypass-condition").long("bypass-condition")
+				.help("Sends all the EEWs regardless of the condition of the destination")))
+}
+
+fn parse_log_level(s: &str) -> Level
+{
+	match s {
+		"critical" => Level::Critical,
+		"error" => Level::Error,
+		"warning" => Level::Warning,
+		"debug" => Level::Debug,
+		_ => Level::Info,
+	}
+}
+
+fn main()
+{
+	let matches = build_cli().get_matches();
+
+	if matches.is_present("version") {
+		eprintln!("Tina - EEW Client ({})", VERSION);
+		return;
+	}
+
+	let (cmd_name, cmd_matches) = matches.subcommand();
+
+	// `tina <path>` is still accepted for backward compatibility
+	let conf_path_arg = matches.value_of("config")
+		.or_else(|| cmd_matches.and_then(|m| m.value_of("config")))
+		.or_else(|| cmd_matches.and_then(|m| m.value_of("path")))
+		.or_else(|| matches.value_of("legacy-config"));
 	let conf_path_env_owned = env::var(CONF_PATH_ENV_VAR).ok();
 	let conf_path_env = conf_path_env_owned.as_ref().map(|s| s.as_str());
 	let conf_path = conf_path_arg.or(conf_path_env).unwrap_or(DEFAULT_CONFIG_PATH);
 
-	let conf = match Config::load_config(conf_path) {
+	if cmd_name == "check-config" {
+		process::exit(check_config_command(conf_path));
+	}
+
+	let mut conf = match Config::load_config(conf_path) {
 		Err(err) => {
-			println!("Error while loading config from '{}' ({:?})", conf_path, err);
-			return;
+			eprintln!("Error while loading config from '{}' ({:?})", conf_path, err);
+			process::exit(1);
 		},
 		Ok(c) => c
 	};
 
+	let log_level = matches.value_of("log-level").or_else(|| cmd_matches.and_then(|m| m.value_of("log-level")));
+	if let Some(level) = log_level {
+		conf.log.log_level = parse_log_level(level);
+	}
+
 	let stdout_drain = FullFormat::new(PlainSyncDecorator::new(stdout())).build();
 	let stdout_logger = Logger::root(stdout_drain.fuse(), o!());
 
@@ -123,63 +412,35 @@ fn main()
 	set_global_logger(root_logger).cancel_reset();
 
 	let eew_logger = build_specific_logger(&conf.log.eew_log_path, conf.log.eew_stdout_log, &stdout_logger);
-	let wni_logger = build_specific_logger(&conf.log.wni_log_path, conf.log.wni_stdout_log, &stdout_logger);
-
-	let wni = Wni::new(conf.wni.id.clone(), "40285072".to_owned(), conf.wni.password.clone(),
-		SERVER_LIST_URL.to_owned(), Some(wni_logger));
-	let mut socks: Vec<Box<dyn Routing>> = Vec::new();
-
-	socks.push(Box::new(Router::new(Logging::new(eew_logger), TRUE_CONDITION, "Log")));
-	info!("Enabled: EEW Logging");
-
-	if let Some(ref t) = conf.twitter.as_ref() {
-		let tw = Twitter::new(
-			t.consumer_token.clone(), t.consumer_secret.clone(),
-			t.access_token.clone(), t.access_secret.clone(), t.in_reply_to_enabled, t.updown_enabled);
-		if ! tw.is_valid() {
-			warn!("Twitter: Invalid tokens");
-		} else {
-			match t.cond {
-				Some(ref v) => socks.push(Box::new(Router::new(tw, build_yaml_condition(v.clone()), "Twitter"))),
-				None => socks.push(Box::new(Router::new(tw, TRUE_CONDITION, "Twitter"))),
-			}
-			info!("Enabled: Twitter");
-		}
-	}
 
-	if let Some(ref s) = conf.slack.as_ref() {
-		match Slack::build(&s.webhook_url, s.updown_enabled) {
-			Ok(sl) => {
-				match s.cond {
-					Some(ref v) => socks.push(Box::new(Router::new(sl, build_yaml_condition(v.clone()), "Slack"))),
-					None => socks.push(Box::new(Router::new(sl, TRUE_CONDITION, "Slack"))),
-				}
-				info!("Enabled: Slack");
-			},
-			Err(_) => {
-				warn!("Slack: Invalid webhook url");
-			}
+	let code = match (cmd_name, cmd_matches) {
+		("parse", Some(m)) =>
+			parse_command(&conf, m.value_of("file").unwrap(), m.is_present("json")),
+		("replay", Some(m)) => {
+			let speed = m.value_of("speed").map(|v| v.parse::<f64>().unwrap());
+			replay(&conf, build_routers(&conf, eew_logger, None), m.value_of("file").unwrap(), speed);
+			0
+		},
+		("send-test", Some(m)) => {
+			let kind = match m.value_of("kind") {
+				Some("reference") => Kind::Reference,
+				_ => Kind::Trial,
+			};
+			let interval = Duration::from_secs(m.value_of("interval").unwrap().parse().unwrap());
+			send_test_command(&conf, eew_logger, m.value_of("destination").unwrap(), kind, interval,
+				m.is_present("bypass-condition"))
+		},
+		_ => {
+			// the journal is used only in this mode so that tests and replays are not recorded;
+			// without the journal file, the states are still kept in memory over reloads
+			let journal = open_journal(&conf).unwrap_or_else(||
+				Journal::in_memory(chrono::Duration::seconds(MEMORY_JOURNAL_RETENTION_SECS)));
+			let socks = build_routers(&conf, eew_logger.clone(), Some(&journal));
+			let wni_logger = build_specific_logger(&conf.log.wni_log_path, conf.log.wni_stdout_log, &stdout_logger);
+			run(&conf, conf_path, wni_logger, eew_logger, socks, journal);
+			0
 		}
-	}
-
-	let mut conn_threads = Vec::new();
-	let (eew_tx, eew_rx) = sync_channel(32);
-
-	for thread_num in 0..WNI_THREAD_COUNT {
-
-		let t = spawn_conn_thread(thread_num, wni.clone(),
-			conf.epicenter_dict.clone(), conf.area_dict.clone(), eew_tx.clone());
-		conn_threads.push(t);
-	}
-
-	let mut his = EEWHistory::new(EEW_HISTORY_CAPACITY);
+	};
 
-	loop {
-		let eew = eew_rx.recv().unwrap();
-		if let Some(eew) = his.append(eew) {
-			for s in socks.iter_mut() {
-				s.emit(&eew);
-			}
-		}
-	}
+	process::exit(code);
 }
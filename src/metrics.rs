@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Copy)]
+enum MetricType {
+	Counter,
+	Gauge,
+	Histogram(&'static [f64]),
+}
+
+struct MetricInfo {
+	name: &'static str,
+	help: &'static str,
+	metric_type: MetricType,
+}
+
+const DELAY_BUCKETS: &'static [f64] = &[0.05, 0.1, 0.25, 0.5, 1.0, 2.0, 5.0, 10.0];
+
+const METRICS: &'static [MetricInfo] = &[
+	MetricInfo { name: "tina_wni_connected_threads",
+		help: "Number of WNI threads currently connected", metric_type: MetricType::Gauge },
+	MetricInfo { name: "tina_wni_reconnects_total",
+		help: "Number of WNI reconnections by error", metric_type: MetricType::Counter },
+	MetricInfo { name: "tina_telegram_delay_seconds",
+		help: "Delay of telegrams from WNI", metric_type: MetricType::Histogram(DELAY_BUCKETS) },
+	MetricInfo { name: "tina_parse_errors_total",
+		help: "Number of telegrams which failed to be parsed by error", metric_type: MetricType::Counter },
+	MetricInfo { name: "tina_eews_total",
+		help: "Number of EEWs accepted or deduplicated by EEWHistory", metric_type: MetricType::Counter },
+	MetricInfo { name: "tina_router_eews_total",
+		help: "Number of EEWs filtered, sent or failed to be sent by each router", metric_type: MetricType::Counter },
+];
+
+#[derive(Debug, Clone)]
+struct Histogram {
+	counts: Vec<u64>,
+	sum: f64,
+	count: u64,
+}
+
+#[derive(Debug, Clone)]
+enum Value {
+	Scalar(f64),
+	Histogram(Histogram),
+}
+
+// a process-wide registry rendered in the Prometheus text format;
+// (metric name, formatted labels) -> value
+static REGISTRY: Mutex<BTreeMap<(&'static str, String), Value>> = Mutex::new(BTreeMap::new());
+
+fn find_info(name: &str) -> &'static MetricInfo
+{
+	METRICS.iter().find(|m| m.name == name).expect("metrics should be declared in METRICS")
+}
+
+fn escape(value: &str) -> String
+{
+	value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn format_labels(labels: &[(&str, &str)]) -> String
+{
+	let pairs: Vec<String> = labels.iter().map(|&(k, v)| format!("{}=\"{}\"", k, escape(v))).collect();
+	pairs.join(",")
+}
+
+fn update<F>(name: &'static str, labels: &[(&str, &str)], f: F) where F: FnOnce(&mut Value)
+{
+	let info = find_info(name);
+	let mut registry = REGISTRY.lock().unwrap();
+
+	let value = registry.entry((name, format_labels(labels))).or_insert_with(|| match info.metric_type {
+		MetricType::Histogram(buckets) =>
+			Value::Histogram(Histogram { counts: vec![0; buckets.len()], sum: 0.0, count: 0 }),
+		_ => Value::Scalar(0.0),
+	});
+
+	f(value);
+}
+
+fn with_label(labels: &str, extra: &str) -> String
+{
+	match (labels.is_empty(), extra.is_empty()) {
+		(true, true) => String::new(),
+		(true, false) => format!("{{{}}}", extra),
+		(false, true) => format!("{{{}}}", labels),
+		(false, false) => format!("{{{},{}}}", labels, extra),
+	}
+}
+
+pub struct Metrics;
+
+impl Metrics {
+
+	pub fn inc_counter(name: &'static str, labels: &[(&str, &str)])
+	{
+		Metrics::add_gauge(name, labels, 1.0);
+	}
+
+	pub fn add_gauge(name: &'static str, labels: &[(&str, &str)], delta: f64)
+	{
+		update(name, labels, |v| if let Value::Scalar(ref mut x) = *v { *x += delta });
+	}
+
+	pub fn observe(name: &'static str, labels: &[(&str, &str)], sample: f64)
+	{
+		let buckets = match find_info(name).metric_type {
+			MetricType::Histogram(buckets) => buckets,
+			_ => return,
+		};
+
+		update(name, labels, |v| if let Value::Histogram(ref mut h) = *v {
+			for (count, bound) in h.counts.iter_mut().zip(buckets.iter()) {
+				if sample <= *bound {
+					*count += 1;
+				}
+			}
+			h.sum += sample;
+			h.count += 1;
+		});
+	}
+
+	pub fn render() -> String
+	{
+		let registry = REGISTRY.lock().unwrap();
+		let mut out = String::new();
+
+		for info in METRICS.iter() {
+
+			let type_name = match info.metric_type {
+				MetricType::Counter => "counter",
+				MetricType::Gauge => "gauge",
+				MetricType::Histogram(_) => "histogram",
+			};
+
+			write_unwrap!(&mut out, "# HELP {} {}\n# TYPE {} {}\n", info.name, info.help, info.name, type_name);
+
+			let entries = registry.range((info.name, String::new())..).take_while(|e| (e.0).0 == info.name);
+
+			for ((_, labels), value) in entries {
+				match (value, info.metric_type) {
+					(Value::Scalar(x), _) => write_unwrap!(&mut out, "{}{} {}\n", info.name, with_label(labels, ""), x),
+					(Value::Histogram(h), MetricType::Histogram(buckets)) => {
+						for (count, bound) in h.counts.iter().zip(buckets.iter()) {
+							write_unwrap!(&mut out, "{}_bucket{} {}\n", info.name,
+								with_label(labels, &format!("le=\"{}\"", bound)), count);
+						}
+						write_unwrap!(&mut out, "{}_bucket{} {}\n", info.name, with_label(labels, "le=\"+Inf\""), h.count);
+						write_unwrap!(&mut out, "{}_sum{} {}\n", info.name, with_label(labels, ""), h.sum);
+						write_unwrap!(&mut out, "{}_count{} {}\n", info.name, with_label(labels, ""), h.count);
+					},
+					_ => {},
+				}
+			}
+		}
+
+		out
+	}
+}
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc, Duration};
+
+use eew::{EEW, IntensityClass};
+use intensity::estimate_intensity_class;
+use travel_time::TravelTimeTable;
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+// average crustal velocities for a rough estimate in a homogeneous medium, which is used without the travel time table
+// (or out of its range); the arrival may be off by several seconds for deep or distant earthquakes
+pub const P_WAVE_VELOCITY_KM_S: f64 = 7.0;
+pub const S_WAVE_VELOCITY_KM_S: f64 = 4.0;
+
+// a named location (e.g. an office) to estimate the arrival of seismic waves and the intensity at;
+// `amplification` is the site amplification factor of PGV relative to the engineering bedrock
+// (e.g. from J-SHIS, or `site_amplification` of AVS30); `travel_times` is set from `path.travel_time` of the config
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Observer {
+	pub name: String,
+	pub lat: f32,
+	pub lon: f32,
+	#[serde(default="def_amplification")] pub amplification: f32,
+	#[serde(skip)] pub travel_times: Option<Arc<TravelTimeTable>>,
+}
+
+fn def_amplification() -> f32 { 1.0 }
+
+#[derive(PartialEq, Debug, Clone, Serialize)]
+pub struct Arrival {
+	pub observer: String,
+	pub epicentral_distance_km: f32,
+	pub distance_km: f32,
+	pub p_wave_at: DateTime<Utc>,
+	pub s_wave_at: DateTime<Utc>,
+}
+
+// great-circle distance by the haversine formula
+pub fn epicentral_distance_km(from: (f32, f32), to: (f32, f32)) -> f32
+{
+	let (lat1, lon1) = ((from.0 as f64).to_radians(), (from.1 as f64).to_radians());
+	let (lat2, lon2) = ((to.0 as f64).to_radians(), (to.1 as f64).to_radians());
+
+	let a = ((lat2 - lat1) / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+	(2.0 * EARTH_RADIUS_KM * a.sqrt().min(1.0).asin()) as f32
+}
+
+fn seconds(secs: f64) -> Duration
+{
+	Duration::milliseconds((secs * 1000.0).round() as i64)
+}
+
+impl Observer {
+
+	// None for EEWs without details (i.e. cancels);
+	// an unknown depth is regarded as the surface, which gives the earliest arrival
+	pub fn arrival(&self, eew: &EEW) -> Option<Arrival>
+	{
+		let detail = eew.detail.as_ref()?;
+
+		let epicentral = epicentral_distance_km(detail.epicenter, (self.lat, self.lon));
+		let depth = detail.depth.unwrap_or(0.0);
+		let distance = (epicentral * epicentral + depth * depth).sqrt();
+
+		let (p, s) = match self.travel_times.as_ref().and_then(|t| t.travel_times(depth, epicentral)) {
+			Some((p, s)) => (p as f64, s as f64),
+			None => (distance as f64 / P_WAVE_VELOCITY_KM_S, distance as f64 / S_WAVE_VELOCITY_KM_S),
+		};
+
+		Some(Arrival {
+			observer: self.name.clone(),
+			epicentral_distance_km: epicentral,
+			distance_km: distance,
+			p_wave_at: eew.occurred_at + seconds(p),
+			s_wave_at: eew.occurred_at + seconds(s),
+		})
+	}
+
+	pub fn estimated_intensity(&self, eew: &EEW) -> Option<IntensityClass>
+	{
+		estimate_intensity_class(eew, (self.lat, self.lon), self.amplification)
+	}
+}
+
+impl Arrival {
+
+	// negative when the wave has already arrived at `t`
+	pub fn p_wave_secs_from(&self, t: DateTime<Utc>) -> f32
+	{
+		(self.p_wave_at - t).num_milliseconds() as f32 / 1000.0
+	}
+
+	pub fn s_wave_secs_from(&self, t: DateTime<Utc>) -> f32
+	{
+		(self.s_wave_at - t).num_milliseconds() as f32 / 1000.0
+	}
+}
@@ -18,7 +18,6 @@ pub enum JMAFormatParseError {
 	InvalidId,
 	InvalidStatus,
 	InvalidNumber,
-	UnknownEpicenterCode,
 	InvalidLL,
 	InvalidDepth,
 	InvalidMagnitude,
@@ -33,7 +32,6 @@ pub enum JMAFormatParseError {
 	InvalidWaveStatus,
 	InvalidEBI,
 	PrematureEOS,
-	UnknownAreaCode,
 }
 
 fn parse_datetime(datetime_text: &[u8]) -> Option<DateTime<Utc>>
@@ -129,7 +127,7 @@ pub fn parse_jma_format(text: &[u8],
 
 	let issued_at = try!(parse_datetime(&text[9..21]).ok_or(JMAFormatParseError::InvalidIssueTime));
 
-	// TODO: accept split telegrams
+	// split telegrams should be joined by TelegramAssembler beforehand
 	if text[24] != b'1' {
 		return Err(JMAFormatParseError::Split);
 	}
@@ -165,9 +163,14 @@ pub fn parse_jma_format(text: &[u8],
 			detail: None,
 		});
 	}
+	let epicenter_code = String::from_utf8_lossy(&text[86..89]).into_owned();
 	let epicenter_name = match epicenter_code_dict.get(&text[86..89]) {
 		Some(s) => s.clone(),
-		None => return Err(JMAFormatParseError::UnknownEpicenterCode)
+		// an unknown code (e.g. an outdated code list) should not drop the whole EEW
+		None => {
+			warn!("Parser: unknown epicenter code {} (the code list may be outdated)", epicenter_code);
+			epicenter_code.clone()
+		}
 	};
 
 	let lat_value = match parse_number(&text[91..94]) {
@@ -315,9 +318,13 @@ pub fn parse_jma_format(text: &[u8],
 
 			let part = &text[it..(it + EBI_PART_LEN)];
 
+			let area_code = String::from_utf8_lossy(&part[1..4]).into_owned();
 			let area_name = match area_code_dict.get(&part[1..4]) {
 				Some(s) => s.clone(),
-				None => return Err(JMAFormatParseError::UnknownAreaCode)
+				None => {
+					warn!("Parser: unknown area code {} (the code list may be outdated)", area_code);
+					area_code.clone()
+				}
 			};
 
 			let left_intensity =
@@ -366,6 +373,7 @@ pub fn parse_jma_format(text: &[u8],
 			};
 
 			let area_eew = AreaEEW {
+				area_code: area_code,
 				area_name: area_name,
 				minimum_intensity: minimum_intensity,
 				maximum_intensity: maximum_intensity,
@@ -386,6 +394,7 @@ pub fn parse_jma_format(text: &[u8],
 
 	let detail = EEWDetail {
 
+		epicenter_code: epicenter_code,
 		epicenter_name: epicenter_name,
 		epicenter: (lat, lon),
 		depth: depth,
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::mem;
+use std::thread::{JoinHandle, spawn};
+use std::time::Duration;
+
+use slog::Logger;
+
+use config::{Config, build_yaml_condition};
+use condition::TRUE_CONDITION;
+use destination::{Twitter, Logging, Slack, PushBullet, Discord, Mqtt, EEWStream, Webhook, Exec, Router, Routing};
+use journal::Journal;
+
+const STREAM_ROUTER_NAME: &'static str = "EEWStream";
+
+// builds the routers of the enabled destinations; the states are restored from `journal` and recorded to it
+pub fn build_routers(conf: &Config, eew_logger: Logger, journal: Option<&Journal>) -> Vec<Box<dyn Routing>>
+{
+	build_routers_with(conf, eew_logger, journal, &mut HashMap::new())
+}
+
+// each new router takes over from the thread of the same name in `predecessors` (see `Router::with_predecessor`)
+fn build_routers_with(conf: &Config, eew_logger: Logger, journal: Option<&Journal>,
+	predecessors: &mut HashMap<String, JoinHandle<()>>) -> Vec<Box<dyn Routing>>
+{
+	let mut socks: Vec<Box<dyn Routing>> = Vec::new();
+
+	socks.push(Box::new(Router::new(Logging::new(eew_logger), TRUE_CONDITION, "Log")));
+	info!("Enabled: EEW Logging");
+
+	if let Some(t) = conf.twitter.as_ref() {
+		let tw = Twitter::new(
+			t.consumer_token.clone(), t.consumer_secret.clone(),
+			t.access_token.clone(), t.access_secret.clone(), t.in_reply_to_enabled, t.updown_enabled,
+			conf.translator(t.language, &t.template));
+		if ! tw.is_valid() {
+			warn!("Twitter: Invalid tokens");
+		} else {
+			match t.cond {
+				Some(ref v) => socks.push(Box::new(Router::with_predecessor(tw, build_yaml_condition(v.clone(), &conf.observers), "Twitter", journal.cloned(), predecessors.remove("Twitter")))),
+				None => socks.push(Box::new(Router::with_predecessor(tw, TRUE_CONDITION, "Twitter", journal.cloned(), predecessors.remove("Twitter")))),
+			}
+			info!("Enabled: Twitter");
+		}
+	}
+
+	if let Some(s) = conf.slack.as_ref() {
+		let slack = match (s.webhook_url.as_ref(), s.bot_token.as_ref(), s.channel.as_ref()) {
+			(Some(url), None, None) =>
+				Slack::build(url, s.updown_enabled, conf.translator(s.language, &s.template)).map_err(|_| "Invalid webhook url"),
+			(None, Some(token), Some(channel)) => {
+				let sl = Slack::with_bot_token(token.clone(), channel.clone(), s.updown_enabled,
+					conf.translator(s.language, &s.template));
+				if sl.is_valid() { Ok(sl) } else { Err("Invalid bot token or channel") }
+			},
+			_ => Err("Either webhook_url or bot_token with channel must be specified"),
+		};
+		match slack {
+			Ok(sl) => {
+				match s.cond {
+					Some(ref v) => socks.push(Box::new(Router::with_predecessor(sl, build_yaml_condition(v.clone(), &conf.observers), "Slack", journal.cloned(), predecessors.remove("Slack")))),
+					None => socks.push(Box::new(Router::with_predecessor(sl, TRUE_CONDITION, "Slack", journal.cloned(), predecessors.remove("Slack")))),
+				}
+				info!("Enabled: Slack");
+			},
+			Err(e) => {
+				warn!("Slack: {}", e);
+			}
+		}
+	}
+
+	if let Some(p) = conf.pushbullet.as_ref() {
+		let pb = PushBullet::new(p.access_token.clone(), p.device_iden.clone(), p.channel_tag.clone(),
+			p.updown_enabled, conf.translator(p.language, &p.template));
+		if ! pb.is_valid() {
+			warn!("PushBullet: Invalid access token");
+		} else {
+			match p.cond {
+				Some(ref v) => socks.push(Box::new(Router::with_predecessor(pb, build_yaml_condition(v.clone(), &conf.observers), "PushBullet", journal.cloned(), predecessors.remove("PushBullet")))),
+				None => socks.push(Box::new(Router::with_predecessor(pb, TRUE_CONDITION, "PushBullet", journal.cloned(), predecessors.remove("PushBullet")))),
+			}
+			info!("Enabled: PushBullet");
+		}
+	}
+
+	if let Some(d) = conf.discord.as_ref() {
+		match Discord::build(&d.webhook_url, d.updown_enabled, conf.translator(d.language, &d.template)) {
+			Ok(dc) => {
+				match d.cond {
+					Some(ref v) => socks.push(Box::new(Router::with_predecessor(dc, build_yaml_condition(v.clone(), &conf.observers), "Discord", journal.cloned(), predecessors.remove("Discord")))),
+					None => socks.push(Box::new(Router::with_predecessor(dc, TRUE_CONDITION, "Discord", journal.cloned(), predecessors.remove("Discord")))),
+				}
+				info!("Enabled: Discord");
+			},
+			Err(_) => {
+				warn!("Discord: Invalid webhook url");
+			}
+		}
+	}
+
+	if let Some(m) = conf.mqtt.as_ref() {
+		let credentials = m.username.clone().map(|u| (u, m.password.clone().unwrap_or_default()));
+		let mq = Mqtt::new(m.host.clone(), m.port, m.client_id.clone(), credentials,
+			m.topic_prefix.clone(), m.qos, m.retain);
+		if ! mq.is_valid() {
+			warn!("Mqtt: Invalid client id, topic prefix or qos");
+		} else {
+			match m.cond {
+				Some(ref v) => socks.push(Box::new(Router::with_predecessor(mq, build_yaml_condition(v.clone(), &conf.observers), "Mqtt", journal.cloned(), predecessors.remove("Mqtt")))),
+				None => socks.push(Box::new(Router::with_predecessor(mq, TRUE_CONDITION, "Mqtt", journal.cloned(), predecessors.remove("Mqtt")))),
+			}
+			info!("Enabled: Mqtt");
+		}
+	}
+
+	if let Some(s) = conf.stream.as_ref() {
+		match EEWStream::start(&s.listen, Duration::from_secs(s.active_secs)) {
+			Ok(st) => {
+				info!("Enabled: EEWStream ({})", st.local_addr());
+				match s.cond {
+					Some(ref v) => socks.push(Box::new(Router::with_predecessor(st, build_yaml_condition(v.clone(), &conf.observers), STREAM_ROUTER_NAME, journal.cloned(), predecessors.remove(STREAM_ROUTER_NAME)))),
+					None => socks.push(Box::new(Router::with_predecessor(st, TRUE_CONDITION, STREAM_ROUTER_NAME, journal.cloned(), predecessors.remove(STREAM_ROUTER_NAME)))),
+				}
+			},
+			Err(e) => {
+				warn!("EEWStream: failed to listen on {} ({})", s.listen, e);
+			}
+		}
+	}
+
+	if let Some(w) = conf.webhook.as_ref() {
+		match Webhook::build(&w.url, &w.headers, Duration::from_secs(w.timeout_secs), w.retry_count) {
+			Ok(wh) => {
+				match w.cond {
+					Some(ref v) => socks.push(Box::new(Router::with_predecessor(wh, build_yaml_condition(v.clone(), &conf.observers), "Webhook", journal.cloned(), predecessors.remove("Webhook")))),
+					None => socks.push(Box::new(Router::with_predecessor(wh, TRUE_CONDITION, "Webhook", journal.cloned(), predecessors.remove("Webhook")))),
+				}
+				info!("Enabled: Webhook");
+			},
+			Err(_) => {
+				warn!("Webhook: Invalid url or headers");
+			}
+		}
+	}
+
+	for e in conf.exec.iter().flat_map(|v| v.iter()) {
+		let ex = Exec::new(e.program.clone(), e.args.clone(),
+			Duration::from_secs(e.timeout_secs), e.max_concurrency);
+		// the name is also the key of the states in the journal, so it has to be unique
+		let mut name = format!("Exec ({})", e.program);
+		let same_count = socks.iter().filter(|s| s.name() == name || s.name().starts_with(&format!("{} #", name))).count();
+		if same_count > 0 {
+			name = format!("{} #{}", name, same_count + 1);
+		}
+		info!("Enabled: {}", name);
+		let predecessor = predecessors.remove(&name);
+		match e.cond {
+			Some(ref v) => socks.push(Box::new(Router::with_predecessor(ex, build_yaml_condition(v.clone(), &conf.observers), name, journal.cloned(), predecessor))),
+			None => socks.push(Box::new(Router::with_predecessor(ex, TRUE_CONDITION, name, journal.cloned(), predecessor))),
+		}
+	}
+
+	socks
+}
+
+// rebuilds the routers from the config file and returns the new config; the current ones are kept if it is invalid.
+// the prev buffers and the destination states are restored from `journal` like at startup; each new router
+// restores the states after the old one of the same name finishes its queued EEWs, so that the dispatch is not blocked
+// and no state recorded while draining is lost
+pub fn reload_routers(conf_path: &str, socks: &mut Vec<Box<dyn Routing>>, eew_logger: &Logger, journal: &Journal)
+	-> Option<Config>
+{
+	let conf = match Config::load_config(conf_path) {
+		Err(err) => {
+			error!("Reload: Error while loading config from '{}' ({:?}); keeping the current one", conf_path, err);
+			return None;
+		},
+		Ok(c) => c
+	};
+
+	let mut old = mem::take(socks);
+
+	// the listening port has to be released before the new stream binds it;
+	// this does not take long since the stream never writes to clients on the destination thread
+	if let Some(i) = old.iter().position(|s| s.name() == STREAM_ROUTER_NAME) {
+		drop(old.remove(i));
+	}
+
+	let mut predecessors: HashMap<_, _> = old.iter_mut()
+		.filter_map(|s| s.close().map(|h| (s.name().to_owned(), h)))
+		.collect();
+	drop(old);
+
+	*socks = build_routers_with(&conf, eew_logger.clone(), Some(journal), &mut predecessors);
+
+	// the routers of the disabled destinations
+	spawn(move || {
+		for (_, handle) in predecessors {
+			if handle.join().is_err() {
+				error!("Reload: destination thread panicked");
+			}
+		}
+	});
+
+	info!("Reload: config reloaded from '{}' (wni, http, log and journal sections are not reloaded)", conf_path);
+	Some(conf)
+}
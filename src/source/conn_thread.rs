@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::SyncSender;
+use std::thread::{spawn, JoinHandle};
+
+use eew::EEW;
+use source::{Wni, WniError};
+use api::{TinaState, ConnectionStatus};
+use moderator::Moderator;
+use metrics::Metrics;
+
+fn error_kind(e: &WniError) -> &'static str
+{
+	match *e {
+		WniError::Authentication => "Authentication",
+		WniError::Network => "Network",
+		WniError::ConnectionClosed => "ConnectionClosed",
+		WniError::InvalidData => "InvalidData",
+		WniError::TooSlow => "TooSlow",
+		WniError::ParseError(_) => "ParseError",
+	}
+}
+
+fn update_status<F>(state: &Mutex<TinaState>, thread_num: u32, f: F) where F: FnOnce(&mut ConnectionStatus)
+{
+	f(&mut state.lock().unwrap().connections[thread_num as usize]);
+}
+
+// keeps a connection to WNI and sends received EEWs to `sock`;
+// reconnects immediately on streaming errors and with a backoff on connection errors
+pub fn spawn_conn_thread(thread_num: u32, wni: Wni,
+	epicenter_dict: HashMap<[u8; 3], String>, area_dict: HashMap<[u8; 3], String>,
+	sock: SyncSender<EEW>, state: Arc<Mutex<TinaState>>) -> JoinHandle<()>
+{
+	spawn(move || {
+
+		let mut moderator = Moderator::new();
+
+		loop {
+
+			let mut connection = match wni.connect() {
+				Ok(v) => v,
+				Err(e) => {
+					error!("Thread {} - ConnectionError: {:?}", thread_num, e);
+					Metrics::inc_counter("tina_wni_reconnects_total", &[("error", error_kind(&e))]);
+					update_status(&state, thread_num, |s| s.set_error(format!("{:?}", e)));
+					moderator.wait_for_retry();
+					moderator.add_count();
+					continue;
+				}
+			};
+
+			moderator.reset();
+			info!("Thread {} - Connected: WNI ({})", thread_num, connection.server());
+			update_status(&state, thread_num, |s| s.set_connected(connection.server().to_owned()));
+			Metrics::add_gauge("tina_wni_connected_threads", &[], 1.0);
+
+			loop {
+
+				let eew = match connection.wait_for_telegram(&epicenter_dict, &area_dict) {
+					Err(e) => {
+						error!("Thread {} - StreamingError: {:?}", thread_num, e);
+						update_status(&state, thread_num, |s| s.set_error(format!("{:?}", e)));
+						Metrics::add_gauge("tina_wni_connected_threads", &[], -1.0);
+						Metrics::inc_counter("tina_wni_reconnects_total", &[("error", error_kind(&e))]);
+						break;
+					},
+					Ok(eew) => eew
+				};
+
+				update_status(&state, thread_num, |s| s.set_received());
+				// waits while the dispatcher is busy instead of dropping the EEW
+				if sock.send(eew).is_err() {
+					return;
+				}
+			}
+		}
+	})
+}
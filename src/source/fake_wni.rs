@@ -0,0 +1,256 @@
+use std::io;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::thread::{spawn, sleep};
+use std::time::Duration;
+use std::collections::VecDeque;
+
+use chrono::{Utc, Duration as ChronoDuration};
+use md5;
+
+use source::wni::{to_header_date, to_wni_time};
+
+// an action taken by FakeWniServer on each connection
+#[derive(Debug, Clone)]
+pub enum FakeWniEvent {
+	KeepAlive,
+	Telegram { data: Vec<u8>, delay_ms: i64 },
+	Wait(Duration),
+	Close,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FakeWniStats {
+	pub server_list_requests: usize,
+	pub connections: usize,
+	pub failed_logins: usize,
+	pub responses: usize,
+}
+
+#[derive(Debug)]
+struct FakeWniState {
+	wni_id: String,
+	hashed_password: String,
+	scripts: VecDeque<Vec<FakeWniEvent>>,
+	keep_alive_interval: Option<Duration>,
+	stats: FakeWniStats,
+}
+
+// a local FastCaster server speaking the same protocol as the WNI servers
+pub struct FakeWniServer {
+	server_list_addr: SocketAddr,
+	fast_caster_addr: SocketAddr,
+	state: Arc<Mutex<FakeWniState>>,
+}
+
+fn read_headers(reader: &mut BufReader<TcpStream>) -> io::Result<Vec<String>>
+{
+	let mut headers = Vec::new();
+
+	loop {
+		let mut line = String::new();
+		if reader.read_line(&mut line)? == 0 {
+			return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "connection closed"));
+		}
+		let line = line.trim_end_matches(&['\r', '\n'][..]).to_owned();
+		if line.is_empty() {
+			return Ok(headers);
+		}
+		headers.push(line);
+	}
+}
+
+fn write_frame(stream: &mut TcpStream, wni_id: &str, delay_ms: i64, body: &[u8]) -> io::Result<()>
+{
+	let now = Utc::now();
+	let sent_at = now - ChronoDuration::milliseconds(delay_ms);
+
+	let mut frame = format!("\
+		GET / HTTP/1.1\n\
+		Content-Type: application/fast-cast\n\
+		Date: {}\n\
+		Server: FastCaster/1.0.0 (Unix)\n\
+		X-WNI-ID: {}\n\
+		X-WNI-Protocol-Version: 2.1\n\
+		X-WNI-Time: {}\n\n",
+		to_header_date(&now),
+		wni_id,
+		to_wni_time(&sent_at)
+	).into_bytes();
+
+	frame.extend_from_slice(body);
+
+	stream.write_all(&frame)?;
+	stream.flush()
+}
+
+fn wait_for_response(reader: &mut BufReader<TcpStream>, state: &Mutex<FakeWniState>) -> io::Result<()>
+{
+	let headers = read_headers(reader)?;
+
+	if headers.iter().any(|h| h == "X-WNI-Result: OK") {
+		state.lock().unwrap().stats.responses += 1;
+	}
+
+	Ok(())
+}
+
+fn serve_server_list(stream: TcpStream, fast_caster_addr: SocketAddr) -> io::Result<()>
+{
+	let mut reader = BufReader::new(stream.try_clone()?);
+	let mut stream = stream;
+	read_headers(&mut reader)?;
+
+	let body = format!("{}\n", fast_caster_addr);
+	let resp = format!("\
+		HTTP/1.0 200 OK\r\n\
+		Content-Type: text/plain\r\n\
+		Content-Length: {}\r\n\
+		Connection: close\r\n\r\n{}",
+		body.len(), body);
+
+	stream.write_all(resp.as_bytes())?;
+	stream.flush()
+}
+
+fn serve_fast_caster(stream: TcpStream, state: Arc<Mutex<FakeWniState>>) -> io::Result<()>
+{
+	let mut reader = BufReader::new(stream.try_clone()?);
+	let mut stream = stream;
+
+	let request = read_headers(&mut reader)?;
+
+	let (authorized, script, keep_alive_interval) = {
+		let mut state = state.lock().unwrap();
+		state.stats.connections += 1;
+
+		let account = format!("X-WNI-Account: {}", state.wni_id);
+		let password = format!("X-WNI-Password: {}", state.hashed_password);
+		let authorized = request.contains(&account) && request.contains(&password);
+
+		if ! authorized {
+			state.stats.failed_logins += 1;
+		}
+
+		(authorized, state.scripts.pop_front().unwrap_or_default(), state.keep_alive_interval)
+	};
+
+	let now = Utc::now();
+	let resp = format!("\
+		HTTP/1.0 200 OK\n\
+		Content-Type: application/fast-cast\n\
+		Date: {}\n\
+		Server: FastCaster/1.0.0 (Unix)\n\
+		X-WNI-ID: Response\n\
+		X-WNI-Protocol-Version: 2.1\n\
+		X-WNI-Result: {}\n\
+		X-WNI-Time: {}\n\n",
+		to_header_date(&now),
+		if authorized { "OK" } else { "NG" },
+		to_wni_time(&now)
+	);
+
+	stream.write_all(resp.as_bytes())?;
+	stream.flush()?;
+
+	if ! authorized {
+		return Ok(());
+	}
+
+	for event in script.into_iter() {
+		match event {
+			FakeWniEvent::KeepAlive => {
+				write_frame(&mut stream, "Keep-Alive", 0, b"")?;
+				wait_for_response(&mut reader, &state)?;
+			},
+			FakeWniEvent::Telegram { data, delay_ms } => {
+				let mut body = b"\x02\n".to_vec();
+				body.extend_from_slice(&data);
+				body.extend_from_slice(b"\n\x03");
+				write_frame(&mut stream, "Data", delay_ms, &body)?;
+				wait_for_response(&mut reader, &state)?;
+			},
+			FakeWniEvent::Wait(d) => sleep(d),
+			FakeWniEvent::Close => return Ok(()),
+		}
+	}
+
+	match keep_alive_interval {
+		Some(interval) => loop {
+			sleep(interval);
+			write_frame(&mut stream, "Keep-Alive", 0, b"")?;
+			wait_for_response(&mut reader, &state)?;
+		},
+		// keep the connection open until the client closes it
+		None => reader.read_to_end(&mut Vec::new()).map(|_| ()),
+	}
+}
+
+impl FakeWniServer {
+
+	// each connection consumes one script; connections beyond the scripts get an empty one
+	pub fn start(wni_id: &str, wni_password: &str, scripts: Vec<Vec<FakeWniEvent>>,
+		keep_alive_interval: Option<Duration>) -> io::Result<FakeWniServer>
+	{
+		let server_list_listener = TcpListener::bind("127.0.0.1:0")?;
+		let fast_caster_listener = TcpListener::bind("127.0.0.1:0")?;
+		let server_list_addr = server_list_listener.local_addr()?;
+		let fast_caster_addr = fast_caster_listener.local_addr()?;
+
+		let state = Arc::new(Mutex::new(FakeWniState {
+			wni_id: wni_id.to_owned(),
+			hashed_password: format!("{:x}", md5::compute(wni_password.as_bytes())),
+			scripts: scripts.into_iter().collect(),
+			keep_alive_interval: keep_alive_interval,
+			stats: FakeWniStats::default(),
+		}));
+
+		{
+			let state = state.clone();
+			spawn(move || {
+				for stream in server_list_listener.incoming().filter_map(|s| s.ok()) {
+					state.lock().unwrap().stats.server_list_requests += 1;
+					if let Err(e) = serve_server_list(stream, fast_caster_addr) {
+						debug!("FakeWniServer: server list error ({})", e);
+					}
+				}
+			});
+		}
+
+		{
+			let state = state.clone();
+			spawn(move || {
+				for stream in fast_caster_listener.incoming().filter_map(|s| s.ok()) {
+					let state = state.clone();
+					spawn(move || {
+						if let Err(e) = serve_fast_caster(stream, state) {
+							debug!("FakeWniServer: connection error ({})", e);
+						}
+					});
+				}
+			});
+		}
+
+		Ok(FakeWniServer {
+			server_list_addr: server_list_addr,
+			fast_caster_addr: fast_caster_addr,
+			state: state,
+		})
+	}
+
+	pub fn server_list_url(&self) -> String
+	{
+		format!("http://{}/server_list.txt", self.server_list_addr)
+	}
+
+	pub fn fast_caster_addr(&self) -> SocketAddr
+	{
+		self.fast_caster_addr
+	}
+
+	pub fn stats(&self) -> FakeWniStats
+	{
+		self.state.lock().unwrap().stats.clone()
+	}
+}
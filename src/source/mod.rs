@@ -1,3 +1,13 @@
 mod wni;
+mod telegram_assembler;
+mod replay;
+#[cfg(feature = "fake-wni")] mod fake_wni;
+mod conn_thread;
+mod synthetic;
 
-pub use self::wni::Wni;
+pub use self::wni::{Wni, WniConnection, WniError};
+pub use self::telegram_assembler::TelegramAssembler;
+pub use self::replay::{Replay, extract_telegrams};
+#[cfg(feature = "fake-wni")] pub use self::fake_wni::{FakeWniServer, FakeWniEvent, FakeWniStats};
+pub use self::conn_thread::spawn_conn_thread;
+pub use self::synthetic::synthetic_eews;
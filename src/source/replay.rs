@@ -0,0 +1,164 @@
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::collections::HashMap;
+use std::thread::sleep;
+use std::time::Duration;
+
+use eew::EEW;
+use parser::{parse_jma_format, JMAFormatParseError};
+use source::TelegramAssembler;
+use metrics::Metrics;
+
+const SPLIT_TELEGRAM_TIMEOUT_SECS: u64 = 60;
+const TERMINATOR: &'static [u8] = b"9999=";
+
+// replays telegrams recorded in a WNI log (`wni_log_path`) or in a file of raw JMA telegrams
+#[derive(Debug, Clone)]
+pub struct Replay {
+	telegrams: Vec<Vec<u8>>,
+	speed: Option<f64>,
+}
+
+fn parse_hex(c: u8) -> Option<u8>
+{
+	match c {
+		b'0'..=b'9' => Some(c - b'0'),
+		b'a'..=b'f' => Some(c - b'a' + 10),
+		b'A'..=b'F' => Some(c - b'A' + 10),
+		_ => None,
+	}
+}
+
+// reverts the `\xNN` escaping applied by WniConnection::output_log
+fn unescape(data: &[u8]) -> Vec<u8>
+{
+	let mut out = Vec::with_capacity(data.len());
+	let mut i = 0;
+
+	while i < data.len() {
+		if data[i] == b'\\' && i + 3 < data.len() && data[i + 1] == b'x' {
+			if let (Some(h), Some(l)) = (parse_hex(data[i + 2]), parse_hex(data[i + 3])) {
+				out.push(h * 16 + l);
+				i += 4;
+				continue;
+			}
+		}
+		out.push(data[i]);
+		i += 1;
+	}
+
+	out
+}
+
+fn trim(text: &[u8]) -> &[u8]
+{
+	let left = text.iter().position(|c| ! c.is_ascii_whitespace()).unwrap_or(text.len());
+	let right = text.iter().rposition(|c| ! c.is_ascii_whitespace()).map_or(left, |i| i + 1);
+	&text[left..right]
+}
+
+fn extract_framed_telegrams(data: &[u8]) -> Vec<Vec<u8>>
+{
+	let mut telegrams = Vec::new();
+	let mut start = 0;
+
+	while let Some(end) = data[start..].iter().position(|c| *c == b'\x03').map(|i| start + i) {
+
+		// same framing as WniConnection::wait_for_raw_telegram
+		if let Some(left) = data[start..end].iter().rposition(|c| *c == b'\x02').map(|i| start + i + 2) {
+			if left < end - 1 {
+				telegrams.push(data[left..(end - 1)].to_vec());
+			}
+		}
+
+		start = end + 1;
+	}
+
+	telegrams
+}
+
+fn extract_raw_telegrams(data: &[u8]) -> Vec<Vec<u8>>
+{
+	let mut telegrams = Vec::new();
+	let mut start = 0;
+
+	while let Some(end) = data[start..].windows(TERMINATOR.len()).position(|w| w == TERMINATOR) {
+		let end = start + end + TERMINATOR.len();
+		let telegram = trim(&data[start..end]);
+		if telegram.len() > TERMINATOR.len() {
+			telegrams.push(telegram.to_vec());
+		}
+		start = end;
+	}
+
+	telegrams
+}
+
+pub fn extract_telegrams(data: &[u8]) -> Vec<Vec<u8>>
+{
+	let data = unescape(data);
+
+	if data.contains(&b'\x02') {
+		extract_framed_telegrams(&data)
+	} else {
+		extract_raw_telegrams(&data)
+	}
+}
+
+impl Replay {
+
+	pub fn new(telegrams: Vec<Vec<u8>>, speed: Option<f64>) -> Replay
+	{
+		Replay { telegrams: telegrams, speed: speed }
+	}
+
+	pub fn load(path: &str, speed: Option<f64>) -> Result<Replay, io::Error>
+	{
+		let mut data = Vec::new();
+		File::open(path)?.read_to_end(&mut data)?;
+
+		Ok(Replay::new(extract_telegrams(&data), speed))
+	}
+
+	pub fn telegrams(&self) -> &[Vec<u8>]
+	{
+		&self.telegrams
+	}
+
+	// calls `f` for each parsed telegram; when a speed factor is given, telegrams are
+	// delivered with the intervals of their issue times divided by the factor
+	pub fn play<F>(&self, epicenter_dict: &HashMap<[u8; 3], String>,
+		area_dict: &HashMap<[u8; 3], String>, mut f: F)
+		where F: FnMut(Result<EEW, JMAFormatParseError>)
+	{
+		let mut assembler = TelegramAssembler::new(Duration::from_secs(SPLIT_TELEGRAM_TIMEOUT_SECS));
+		let mut last_issued_at = None;
+
+		for raw in self.telegrams.iter() {
+
+			let telegram = match assembler.push(raw) {
+				Some(t) => t,
+				None => continue,
+			};
+
+			let result = parse_jma_format(&telegram, epicenter_dict, area_dict);
+
+			if let Err(ref e) = result {
+				Metrics::inc_counter("tina_parse_errors_total", &[("error", &format!("{:?}", e))]);
+			}
+
+			if let (Some(speed), Ok(eew)) = (self.speed, result.as_ref()) {
+				if let Some(last) = last_issued_at {
+					let delta_ms = eew.issued_at.signed_duration_since(last).num_milliseconds();
+					if delta_ms > 0 && speed > 0.0 {
+						sleep(Duration::from_millis((delta_ms as f64 / speed) as u64));
+					}
+				}
+				last_issued_at = Some(eew.issued_at);
+			}
+
+			f(result);
+		}
+	}
+}
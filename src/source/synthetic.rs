@@ -0,0 +1,115 @@
+use chrono::{DateTime, Utc, Duration};
+
+use eew::{EEW, EEWDetail, AreaEEW, IssuePattern, Source, Kind, Status, EpicenterAccuracy, DepthAccuracy,
+	MagnitudeAccuracy, EpicenterCategory, WarningStatus, IntensityChange, ChangeReason, WaveStatus, IntensityClass};
+
+const CANCELLED_EVENT_DELAY_SECS: i64 = 60;
+
+struct Report {
+	number: u32,
+	status: Status,
+	magnitude: f32,
+	maximum_intensity: IntensityClass,
+	warning_status: WarningStatus,
+	intensity_change: IntensityChange,
+	change_reason: ChangeReason,
+	areas: &'static [(&'static str, &'static str, IntensityClass, i64)],
+}
+
+const AREAS_1: &'static [(&'static str, &'static str, IntensityClass, i64)] = &[
+	("301", "茨城県南部", IntensityClass::Three, 8),
+];
+
+const AREAS_2: &'static [(&'static str, &'static str, IntensityClass, i64)] = &[
+	("301", "茨城県南部", IntensityClass::Four, 8),
+	("331", "埼玉県南部", IntensityClass::Three, 12),
+];
+
+const AREAS_3: &'static [(&'static str, &'static str, IntensityClass, i64)] = &[
+	("301", "茨城県南部", IntensityClass::FiveLower, 8),
+	("331", "埼玉県南部", IntensityClass::Four, 12),
+	("350", "東京都23区", IntensityClass::Four, 15),
+];
+
+// first report, intensity up, alert and last
+const REPORTS: &'static [Report] = &[
+	Report { number: 1, status: Status::Normal, magnitude: 4.6, maximum_intensity: IntensityClass::Three,
+		warning_status: WarningStatus::Forecast, intensity_change: IntensityChange::Unknown,
+		change_reason: ChangeReason::Unknown, areas: AREAS_1 },
+	Report { number: 2, status: Status::Normal, magnitude: 5.4, maximum_intensity: IntensityClass::Four,
+		warning_status: WarningStatus::Forecast, intensity_change: IntensityChange::Up,
+		change_reason: ChangeReason::Magnitude, areas: AREAS_2 },
+	Report { number: 3, status: Status::Normal, magnitude: 6.0, maximum_intensity: IntensityClass::FiveLower,
+		warning_status: WarningStatus::Alert, intensity_change: IntensityChange::Up,
+		change_reason: ChangeReason::Magnitude, areas: AREAS_3 },
+	Report { number: 4, status: Status::Last, magnitude: 6.0, maximum_intensity: IntensityClass::FiveLower,
+		warning_status: WarningStatus::Alert, intensity_change: IntensityChange::Same,
+		change_reason: ChangeReason::Nothing, areas: AREAS_3 },
+];
+
+fn build_eew(kind: Kind, occurred_at: DateTime<Utc>, report: &Report) -> EEW
+{
+	let warning_status = report.warning_status;
+
+	let area_info = report.areas.iter().map(|&(code, name, intensity, reach_secs)| AreaEEW {
+		area_code: code.to_owned(),
+		area_name: name.to_owned(),
+		minimum_intensity: intensity,
+		maximum_intensity: None,
+		reach_at: Some(occurred_at + Duration::seconds(reach_secs)),
+		warning_status: warning_status,
+		wave_status: WaveStatus::Unreached,
+	}).collect();
+
+	EEW {
+		issue_pattern: IssuePattern::HighAccuracy,
+		source: Source::Tokyo,
+		kind: kind,
+		issued_at: occurred_at + Duration::seconds(4 + report.number as i64),
+		occurred_at: occurred_at,
+		id: occurred_at.format("ND%Y%m%d%H%M%S").to_string(),
+		status: report.status,
+		number: report.number,
+		detail: Some(EEWDetail {
+			epicenter_code: "301".to_owned(),
+			epicenter_name: "茨城県南部".to_owned(),
+			epicenter: (36.1, 139.9),
+			depth: Some(50.0),
+			magnitude: Some(report.magnitude),
+			maximum_intensity: Some(report.maximum_intensity),
+			epicenter_accuracy: EpicenterAccuracy::GridSearchHigh,
+			depth_accuracy: DepthAccuracy::GridSearchHigh,
+			magnitude_accuracy: MagnitudeAccuracy::SWave,
+			epicenter_category: EpicenterCategory::Land,
+			warning_status: warning_status,
+			intensity_change: report.intensity_change,
+			change_reason: report.change_reason,
+			plum: false,
+			area_info: area_info,
+		}),
+	}
+}
+
+// a sequence of test EEWs used to check the delivery to destinations without real earthquakes;
+// an event with four reports (first, intensity up, alert and last) is followed by a cancelled event
+pub fn synthetic_eews(occurred_at: DateTime<Utc>, kind: Kind) -> Vec<EEW>
+{
+	assert!(kind == Kind::Trial || kind == Kind::Reference, "synthetic EEWs should be marked as tests");
+
+	let mut eews: Vec<EEW> = REPORTS.iter().map(|r| build_eew(kind, occurred_at, r)).collect();
+
+	let cancelled_at = occurred_at + Duration::seconds(CANCELLED_EVENT_DELAY_SECS);
+	let first = build_eew(kind, cancelled_at, &REPORTS[0]);
+
+	let cancel = EEW {
+		issue_pattern: IssuePattern::Cancel,
+		issued_at: first.issued_at + Duration::seconds(3),
+		number: 2,
+		detail: None,
+		.. first.clone()
+	};
+
+	eews.push(first);
+	eews.push(cancel);
+	eews
+}
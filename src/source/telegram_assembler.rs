@@ -0,0 +1,148 @@
+use std::time::{Duration, Instant};
+
+const HEADER_LEN: usize = 62;
+const EBI_OFFSET: usize = 135;
+const EBI_BODY_OFFSET: usize = 138;
+const PART_NUMBER_POS: usize = 23;
+const PART_COUNT_POS: usize = 24;
+const TERMINATOR: &'static [u8] = b" 9999=";
+
+#[derive(Debug)]
+struct PendingTelegram {
+	key: Vec<u8>,
+	parts: Vec<Option<Vec<u8>>>,
+	created_at: Instant,
+}
+
+// buffers the parts of split JMA telegrams and joins them into a single telegram
+#[derive(Debug)]
+pub struct TelegramAssembler {
+	pending: Vec<PendingTelegram>,
+	timeout: Duration,
+}
+
+fn parse_digit(c: u8) -> Option<usize>
+{
+	match c {
+		b'1'..=b'9' => Some((c - b'0') as usize),
+		_ => None,
+	}
+}
+
+fn split_info(text: &[u8]) -> Option<(usize, usize)>
+{
+	if text.len() < HEADER_LEN {
+		return None;
+	}
+
+	let number = parse_digit(text[PART_NUMBER_POS])?;
+	let count = parse_digit(text[PART_COUNT_POS])?;
+
+	if number > count {
+		return None;
+	}
+
+	Some((number, count))
+}
+
+fn trim_right(text: &[u8]) -> &[u8]
+{
+	let right = text.iter().rposition(|c| ! c.is_ascii_whitespace()).map_or(0, |i| i + 1);
+	&text[0..right]
+}
+
+fn ebi_entries(text: &[u8]) -> &[u8]
+{
+	if text.len() < EBI_BODY_OFFSET || &text[EBI_OFFSET..EBI_BODY_OFFSET] != b"EBI" {
+		return &[];
+	}
+
+	let body = trim_right(&text[EBI_BODY_OFFSET..]);
+
+	match body.ends_with(TERMINATOR) {
+		true => &body[0..(body.len() - TERMINATOR.len())],
+		false => body,
+	}
+}
+
+fn join_parts(parts: &[Vec<u8>]) -> Vec<u8>
+{
+	let first = &parts[0];
+	let entries: Vec<u8> = parts.iter().flat_map(|p| ebi_entries(p).iter().cloned()).collect();
+
+	let mut joined = if entries.is_empty() || first.len() < EBI_OFFSET {
+		trim_right(first).to_vec()
+	} else {
+		let mut t = first[0..EBI_OFFSET].to_vec();
+		t.extend_from_slice(b"EBI");
+		t.extend(entries);
+		t.extend_from_slice(TERMINATOR);
+		t
+	};
+
+	joined[PART_NUMBER_POS] = b'1';
+	joined[PART_COUNT_POS] = b'1';
+
+	joined
+}
+
+impl TelegramAssembler {
+
+	pub fn new(timeout: Duration) -> TelegramAssembler
+	{
+		TelegramAssembler { pending: Vec::new(), timeout: timeout }
+	}
+
+	// returns a complete telegram when `text` is an unsplit telegram or the last missing part;
+	// telegrams which do not look like a JMA telegram are passed through as they are
+	pub fn push(&mut self, text: &[u8]) -> Option<Vec<u8>>
+	{
+		let timeout = self.timeout;
+		self.pending.retain(|p| {
+			let alive = p.created_at.elapsed() <= timeout;
+			if ! alive {
+				warn!("split telegram expired ({})", String::from_utf8_lossy(&p.key));
+			}
+			alive
+		});
+
+		let (number, count) = match split_info(text) {
+			Some((_, 1)) | None => return Some(text.to_vec()),
+			Some(v) => v,
+		};
+
+		// event id and telegram number identify each set of parts
+		let mut key = text[39..55].to_vec();
+		key.extend_from_slice(&text[59..62]);
+
+		let idx = match self.pending.iter().position(|p| p.key == key && p.parts.len() == count) {
+			Some(idx) => idx,
+			None => {
+				self.pending.retain(|p| p.key != key);
+				self.pending.push(PendingTelegram {
+					key: key,
+					parts: vec![None; count],
+					created_at: Instant::now(),
+				});
+				self.pending.len() - 1
+			}
+		};
+
+		self.pending[idx].parts[number - 1] = Some(text.to_vec());
+
+		if self.pending[idx].parts.iter().any(|p| p.is_none()) {
+			debug!("split telegram buffered ({}/{})", number, count);
+			return None;
+		}
+
+		let completed = self.pending.remove(idx);
+		let parts: Vec<Vec<u8>> = completed.parts.into_iter().map(|p| p.expect("all parts exist")).collect();
+
+		Some(join_parts(&parts))
+	}
+
+	pub fn pending_count(&self) -> usize
+	{
+		self.pending.len()
+	}
+}
@@ -12,9 +12,12 @@ use chrono::{DateTime, Utc, TimeZone};
 
 use eew::EEW;
 use parser::{parse_jma_format, JMAFormatParseError};
+use source::TelegramAssembler;
+use metrics::Metrics;
 
 const CONNECTION_TIMEOUT_SECS: u64 = 3 * 60;
 const DELAY_THRESHOLD_MS: i64 = 2000;
+const SPLIT_TELEGRAM_TIMEOUT_SECS: u64 = 10;
 const DATE_FORMAT: &'static str = "%a, %d %b %Y %T%.6f UTC";
 const X_WNI_TIME_FORMAT: &'static str = "%Y/%m/%d %T%.6f";
 
@@ -38,7 +41,7 @@ pub struct Wni {
 	logger: Logger,
 }
 
-fn to_header_date(utc: &DateTime<Utc>) -> String
+pub fn to_header_date(utc: &DateTime<Utc>) -> String
 {
 	utc.format(DATE_FORMAT).to_string()
 }
@@ -48,7 +51,7 @@ fn from_header_date(txt: &[u8]) -> Option<DateTime<Utc>>
 	str::from_utf8(txt).ok().and_then(|s| Utc.datetime_from_str(s, DATE_FORMAT).ok())
 }
 
-fn to_wni_time(utc: &DateTime<Utc>) -> String
+pub fn to_wni_time(utc: &DateTime<Utc>) -> String
 {
 	utc.format(X_WNI_TIME_FORMAT).to_string()
 }
@@ -103,6 +106,7 @@ pub struct WniConnection<'a> {
 	reader: BufReader<TcpStream>,
 	logger: &'a Logger,
 	too_slow: bool,
+	assembler: TelegramAssembler,
 }
 
 impl<'a> WniConnection<'a> {
@@ -124,6 +128,7 @@ impl<'a> WniConnection<'a> {
 			reader: reader,
 			logger: logger,
 			too_slow: false,
+			assembler: TelegramAssembler::new(Duration::from_secs(SPLIT_TELEGRAM_TIMEOUT_SECS)),
 		};
 
 		conn.write_request(wni_id, wni_terminal_id, wni_password)?;
@@ -259,6 +264,26 @@ impl<'a> WniConnection<'a> {
 			return Err(WniError::TooSlow);
 		}
 
+		loop {
+
+			let raw_data = self.wait_for_raw_telegram()?;
+
+			match self.assembler.push(&raw_data) {
+				None => self.write_response()?,
+				Some(telegram) => {
+					let eew = parse_jma_format(&telegram, epicenter_dict, area_dict).map_err(|e| {
+						Metrics::inc_counter("tina_parse_errors_total", &[("error", &format!("{:?}", e))]);
+						WniError::ParseError(e)
+					})?;
+					self.write_response()?;
+					return Ok(eew);
+				}
+			}
+		}
+	}
+
+	fn wait_for_raw_telegram(&mut self) -> Result<Vec<u8>, WniError>
+	{
 		loop {
 
 			let headers = self.read_headers()?;
@@ -278,6 +303,10 @@ impl<'a> WniConnection<'a> {
 					_ => None,
 				};
 
+				if let Some(x) = delta_ms {
+					Metrics::observe("tina_telegram_delay_seconds", &[], x as f64 / 1000.0);
+				}
+
 				match delta_ms {
 					Some(x) if x > DELAY_THRESHOLD_MS => {
 						self.too_slow = true;
@@ -309,13 +338,7 @@ impl<'a> WniConnection<'a> {
 			return Err(WniError::InvalidData);
 		}
 
-		let raw_data = &buffer[left..right];
-		let eew = parse_jma_format(raw_data, epicenter_dict, area_dict)
-			.map_err(|e| WniError::ParseError(e))?;
-
-		self.write_response()?;
-
-		Ok(eew)
+		Ok(buffer[left..right].to_vec())
 	}
 
 	pub fn server(&self) -> &str
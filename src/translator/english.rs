@@ -0,0 +1,92 @@
+use std::cmp::Ordering;
+use std::fmt::Write;
+
+use eew::*;
+use super::japanese::{format_time, compare_intensity};
+
+pub use super::japanese::{format_position, format_magnitude, format_depth};
+
+
+pub fn format_intensity(intensity: Option<IntensityClass>) -> String
+{
+	match intensity {
+		None => "Shindo unknown",
+		Some(IntensityClass::Zero) => "Shindo 0",
+		Some(IntensityClass::One) => "Shindo 1",
+		Some(IntensityClass::Two) => "Shindo 2",
+		Some(IntensityClass::Three) => "Shindo 3",
+		Some(IntensityClass::Four) => "Shindo 4",
+		Some(IntensityClass::FiveLower) => "Shindo 5-",
+		Some(IntensityClass::FiveUpper) => "Shindo 5+",
+		Some(IntensityClass::SixLower) => "Shindo 6-",
+		Some(IntensityClass::SixUpper) => "Shindo 6+",
+		Some(IntensityClass::Seven) => "Shindo 7"
+	}.to_owned()
+}
+
+pub fn format_number(number: u32) -> String
+{
+	format!("#{}", number)
+}
+
+// epicenter and area names are written as they are;
+// use `Translator` to replace them with the names in a companion dictionary
+pub fn format_eew_short(eew: &EEW, prev_opt: Option<&EEW>) -> Option<(String, String, String)>
+{
+	let mut header = String::new();
+	let mut body = String::new();
+	let mut footer = String::new();
+
+	if eew.is_test() {
+		header += "Test | ";
+	}
+
+	if eew.is_drill() {
+		header += "Drill | ";
+	}
+
+	let title = match eew.get_eew_phase() {
+		Some(EEWPhase::Cancel) => "Cancelled",
+		Some(EEWPhase::FastForecast) => "Preliminary",
+		Some(EEWPhase::Forecast) => "Forecast",
+		Some(EEWPhase::Alert) => "Warning",
+		_ => return None,
+	};
+	header += title;
+
+	let updown = match compare_intensity(eew, prev_opt) {
+		Ordering::Greater => "↑",
+		Ordering::Less => "↓",
+		Ordering::Equal => "",
+	};
+	header += updown;
+
+	if eew.is_last() {
+		header += "/Final";
+	}
+
+	match eew.detail {
+
+		None => { body += "---" },
+
+		Some(ref detail) => {
+
+			write_unwrap!(&mut body, "{} {} {} {} ({}) at {} JST",
+				detail.epicenter_name, format_intensity(detail.maximum_intensity),
+				format_magnitude(detail.magnitude), format_depth(detail.depth),
+				format_position(detail.epicenter), format_time(&eew.occurred_at));
+		},
+	}
+
+	write_unwrap!(&mut footer, "{} {}", format_number(eew.number), eew.id);
+
+	Some((header, body, footer))
+}
+
+
+pub fn format_eew_oneline(eew: &EEW, prev_opt: Option<&EEW>) -> Option<String>
+{
+	format_eew_short(eew, prev_opt).map(|s|
+		format!("[{}] {} | {}", s.0, s.1, s.2)
+	)
+}
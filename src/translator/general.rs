@@ -17,19 +17,19 @@ pub fn format_eew_full(eew: &EEW) -> String
 
 	if let Some(ref detail) = eew.detail {
 
-		write_unwrap!(&mut output, "epicenter_name: {}, epicenter: {:?}, \
+		write_unwrap!(&mut output, "epicenter_code: {}, epicenter_name: {}, epicenter: {:?}, \
 			depth: {:?}, magnitude: {:?}, maximum_intensity: {:?}, epicenter_accuracy: {:?}, \
 			depth_accuracy: {:?}, magnitude_accuracy: {:?}, epicenter_category: {:?}, \
 			warning_status: {:?}, intensity_change: {:?}, change_reason: {:?}\n",
-			detail.epicenter_name, detail.epicenter, detail.depth,
+			detail.epicenter_code, detail.epicenter_name, detail.epicenter, detail.depth,
 			detail.magnitude, detail.maximum_intensity, detail.epicenter_accuracy,
 			detail.depth_accuracy, detail.magnitude_accuracy, detail.epicenter_category,
 			detail.warning_status, detail.intensity_change, detail.change_reason);
 
 		for area in detail.area_info.iter() {
-			write_unwrap!(&mut output, "area_name: {}, minimum_intensity: {:?}, \
+			write_unwrap!(&mut output, "area_code: {}, area_name: {}, minimum_intensity: {:?}, \
 				maximum_intensity: {:?}, reach_at: {:?}, warning_status: {:?}, wave_status: {:?}\n",
-				area.area_name, area.minimum_intensity, area.maximum_intensity,
+				area.area_code, area.area_name, area.minimum_intensity, area.maximum_intensity,
 				area.reach_at, area.warning_status, area.wave_status);
 		}
 	}
@@ -15,6 +15,15 @@ pub fn format_time(dt: &DateTime<Utc>) -> DelayedFormat<StrftimeItems>
 	dt.with_timezone(&jst).format(TIME_FORMAT)
 }
 
+// e.g. `reach_at` of areas may be unknown
+pub fn format_optional_time(dt: Option<DateTime<Utc>>) -> String
+{
+	match dt {
+		None => "--:--:--".to_owned(),
+		Some(dt) => format_time(&dt).to_string(),
+	}
+}
+
 pub fn format_position(pos: (f32, f32)) -> String
 {
 	let lat_h = if pos.0 >= 0.0 { "N" } else { "S" };
@@ -55,6 +64,11 @@ pub fn format_intensity(intensity: Option<IntensityClass>) -> String
 	}.to_owned()
 }
 
+pub fn format_number(number: u32) -> String
+{
+	format!("第{}報", number)
+}
+
 pub fn compare_intensity(eew: &EEW, prev_opt: Option<&EEW>) -> Ordering
 {
 	let prev_detail_opt = prev_opt.and_then(|p| p.detail.as_ref());
@@ -121,7 +135,7 @@ pub fn format_eew_short(eew: &EEW, prev_opt: Option<&EEW>) -> Option<(String, St
 		},
 	}
 
-	write_unwrap!(&mut footer, "第{}報 {}", eew.number, eew.id);
+	write_unwrap!(&mut footer, "{} {}", format_number(eew.number), eew.id);
 
 	Some((header, body, footer))
 }
@@ -0,0 +1,40 @@
+use serde_json;
+use serde_json::Value;
+
+use eew::*;
+
+
+pub fn format_phase(phase: EEWPhase) -> &'static str
+{
+	match phase {
+		EEWPhase::Cancel => "cancel",
+		EEWPhase::FastForecast => "fast_forecast",
+		EEWPhase::Forecast => "forecast",
+		EEWPhase::Alert => "alert",
+	}
+}
+
+fn format_summary(eew: &EEW) -> Value
+{
+	let detail = eew.detail.as_ref();
+
+	json!({
+		"id": eew.id,
+		"number": eew.number,
+		"issued_at": eew.issued_at,
+		"phase": eew.get_eew_phase().map(format_phase),
+		"epicenter_name": detail.map(|d| d.epicenter_name.as_str()),
+		"magnitude": detail.and_then(|d| d.magnitude),
+		"maximum_intensity": detail.and_then(|d| d.maximum_intensity),
+	})
+}
+
+pub fn format_eew_json(eew: &EEW, prev_opt: Option<&EEW>) -> Value
+{
+	json!({
+		"version": EEW_JSON_VERSION,
+		"phase": eew.get_eew_phase().map(format_phase),
+		"eew": serde_json::to_value(eew).expect("EEW is always serializable"),
+		"prev": prev_opt.map(format_summary),
+	})
+}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use eew::*;
+use super::{japanese, english};
+use super::template::{Template, TemplateError};
+use observer::Observer;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum Language {
+	#[default] #[serde(rename = "ja")] Japanese,
+	#[serde(rename = "en")] English,
+}
+
+// names of epicenters and areas indexed by the same codes as the Japanese dictionaries
+#[derive(Debug, Clone, Default)]
+pub struct NameDict {
+	pub epicenter: HashMap<[u8; 3], String>,
+	pub area: HashMap<[u8; 3], String>,
+}
+
+fn lookup<'a>(dict: &'a HashMap<[u8; 3], String>, code: &str) -> Option<&'a String>
+{
+	let bytes = code.as_bytes();
+
+	if bytes.len() != 3 {
+		return None;
+	}
+
+	let mut key = [0; 3];
+	key.copy_from_slice(bytes);
+	dict.get(&key)
+}
+
+// selects the formatter by the language; names missing in the dictionary are left in Japanese
+#[derive(Debug, Clone)]
+pub struct Translator {
+	language: Language,
+	names: Arc<NameDict>,
+	template: Option<Template>,
+	observers: Arc<Vec<Observer>>,
+}
+
+impl Default for Translator {
+	fn default() -> Translator { Translator::new(Language::Japanese, Arc::new(NameDict::default())) }
+}
+
+impl Translator {
+
+	pub fn new(language: Language, names: Arc<NameDict>) -> Translator
+	{
+		Translator { language: language, names: names, template: None, observers: Arc::new(Vec::new()) }
+	}
+
+	// the template replaces the body of the short format and the whole one-line format
+	pub fn with_template(self, source: &str) -> Result<Translator, TemplateError>
+	{
+		let template = Template::new(source, self.language)?;
+		Ok(Translator { template: Some(template), .. self })
+	}
+
+	// the arrivals at the observers are available in the template
+	pub fn with_observers(self, observers: Vec<Observer>) -> Translator
+	{
+		Translator { observers: Arc::new(observers), .. self }
+	}
+
+	pub fn language(&self) -> Language
+	{
+		self.language
+	}
+
+	// replaces the epicenter and area names with the ones in the dictionary
+	pub fn localize(&self, eew: &EEW) -> EEW
+	{
+		let mut eew = eew.clone();
+
+		if self.language == Language::Japanese {
+			return eew;
+		}
+
+		if let Some(ref mut detail) = eew.detail {
+
+			if let Some(name) = lookup(&self.names.epicenter, &detail.epicenter_code) {
+				detail.epicenter_name = name.clone();
+			}
+
+			for area in detail.area_info.iter_mut() {
+				if let Some(name) = lookup(&self.names.area, &area.area_code) {
+					area.area_name = name.clone();
+				}
+			}
+		}
+
+		eew
+	}
+
+	// falls back to the default format when the template fails
+	fn render(&self, eew: &EEW, prev_opt: Option<&EEW>) -> Option<String>
+	{
+		let template = self.template.as_ref()?;
+
+		match template.render(eew, prev_opt, &self.observers) {
+			Ok(out) => Some(out),
+			Err(e) => {
+				error!("Translator: failed to render the template ({:?})", e);
+				None
+			}
+		}
+	}
+
+	pub fn format_eew_short(&self, eew: &EEW, prev_opt: Option<&EEW>) -> Option<(String, String, String)>
+	{
+		let eew = self.localize(eew);
+
+		let out = match self.language {
+			Language::Japanese => japanese::format_eew_short(&eew, prev_opt),
+			Language::English => english::format_eew_short(&eew, prev_opt),
+		}?;
+
+		match self.render(&eew, prev_opt) {
+			Some(body) => Some((out.0, body, out.2)),
+			None => Some(out),
+		}
+	}
+
+	pub fn format_eew_oneline(&self, eew: &EEW, prev_opt: Option<&EEW>) -> Option<String>
+	{
+		let eew = self.localize(eew);
+
+		let out = match self.language {
+			Language::Japanese => japanese::format_eew_oneline(&eew, prev_opt),
+			Language::English => english::format_eew_oneline(&eew, prev_opt),
+		}?;
+
+		Some(self.render(&eew, prev_opt).unwrap_or(out))
+	}
+
+	pub fn format_magnitude(&self, m: Option<f32>) -> String
+	{
+		match self.language {
+			Language::Japanese => japanese::format_magnitude(m),
+			Language::English => english::format_magnitude(m),
+		}
+	}
+
+	pub fn format_depth(&self, d: Option<f32>) -> String
+	{
+		match self.language {
+			Language::Japanese => japanese::format_depth(d),
+			Language::English => english::format_depth(d),
+		}
+	}
+
+	pub fn format_intensity(&self, intensity: Option<IntensityClass>) -> String
+	{
+		match self.language {
+			Language::Japanese => japanese::format_intensity(intensity),
+			Language::English => english::format_intensity(intensity),
+		}
+	}
+
+	pub fn format_number(&self, number: u32) -> String
+	{
+		match self.language {
+			Language::Japanese => japanese::format_number(number),
+			Language::English => english::format_number(number),
+		}
+	}
+}
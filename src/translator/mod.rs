@@ -1,6 +1,14 @@
 mod japanese;
+mod english;
+mod language;
+mod template;
 mod general;
+mod json;
 
 pub use self::japanese::format_eew_oneline as ja_format_eew_oneline;
-pub use self::japanese::format_eew_short as ja_format_eew_short;
+pub use self::english::format_eew_oneline as en_format_eew_oneline;
+pub use self::language::{Language, NameDict, Translator};
+pub use self::template::{Template, TemplateError};
 pub use self::general::format_eew_full;
+pub use self::json::format_eew_json;
+pub use self::json::format_phase as json_format_phase;
@@ -0,0 +1,163 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::Arc;
+
+use handlebars;
+use handlebars::{Handlebars, Helper, HelperDef, HelperResult, Context, RenderContext, RenderError, Output};
+use serde_json;
+use serde_json::Value;
+
+use eew::*;
+use eew::lat_lon;
+use observer::Observer;
+use super::{japanese, english, json};
+use super::language::Language;
+
+const TEMPLATE_NAME: &'static str = "message";
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum TemplateError {
+	InvalidSyntax(String),
+	RenderFailure(String),
+}
+
+// a user-defined message in the handlebars syntax;
+// the context is the serialized EEW with some extra variables (see `build_context`)
+#[derive(Clone)]
+pub struct Template {
+	source: String,
+	language: Language,
+	registry: Arc<Handlebars<'static>>,
+}
+
+impl fmt::Debug for Template {
+
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+	{
+		f.debug_struct("Template").field("source", &self.source).field("language", &self.language).finish()
+	}
+}
+
+// `{{name value}}` formats `value` with the function
+struct FormatHelper<F>(F);
+
+impl<F> HelperDef for FormatHelper<F> where F: Fn(Value) -> Result<String, serde_json::Error> + Send + Sync
+{
+	fn call<'reg: 'rc, 'rc>(&self, h: &Helper<'reg, 'rc>, _: &'reg Handlebars<'reg>, _: &'rc Context,
+		_: &mut RenderContext<'reg, 'rc>, out: &mut dyn Output) -> HelperResult
+	{
+		let param = h.param(0).ok_or_else(|| RenderError::new(format!("`{}` requires a value", h.name())))?;
+		let text = (self.0)(param.value().clone())
+			.map_err(|e| RenderError::new(format!("`{}`: {}", h.name(), e)))?;
+		out.write(&text)?;
+		Ok(())
+	}
+}
+
+fn register_format<F>(registry: &mut Handlebars<'static>, name: &str, f: F)
+	where F: Fn(Value) -> Result<String, serde_json::Error> + Send + Sync + 'static
+{
+	registry.register_helper(name, Box::new(FormatHelper(f)));
+}
+
+fn build_registry(language: Language) -> Handlebars<'static>
+{
+	let mut registry = Handlebars::new();
+	registry.register_escape_fn(handlebars::no_escape);
+
+	let format_intensity: fn(Option<IntensityClass>) -> String = match language {
+		Language::Japanese => japanese::format_intensity,
+		Language::English => english::format_intensity,
+	};
+
+	register_format(&mut registry, "intensity", move |v| serde_json::from_value(v).map(format_intensity));
+	register_format(&mut registry, "magnitude", |v| serde_json::from_value(v).map(japanese::format_magnitude));
+	register_format(&mut registry, "depth", |v| serde_json::from_value(v).map(japanese::format_depth));
+	register_format(&mut registry, "position", |v| lat_lon::deserialize(v).map(japanese::format_position));
+	register_format(&mut registry, "time", |v| serde_json::from_value(v).map(japanese::format_optional_time));
+
+	registry
+}
+
+// `name`, `distance_km` (to the hypocenter), `epicentral_distance_km`, `p_wave_at` and `s_wave_at` of each observer,
+// with `p_wave_secs` and `s_wave_secs` from the issue of the EEW (negative if already arrived)
+// and `estimated_intensity` (null without the magnitude);
+// numbers are rounded to integers since templates cannot format them
+fn build_arrivals(eew: &EEW, observers: &[Observer]) -> Value
+{
+	let arrivals: Vec<Value> = observers.iter().filter_map(|o| o.arrival(eew).map(|a| (o, a))).map(|(o, a)| json!({
+		"name": a.observer,
+		"distance_km": a.distance_km.round() as i64,
+		"epicentral_distance_km": a.epicentral_distance_km.round() as i64,
+		"p_wave_at": a.p_wave_at,
+		"s_wave_at": a.s_wave_at,
+		"p_wave_secs": a.p_wave_secs_from(eew.issued_at).round() as i64,
+		"s_wave_secs": a.s_wave_secs_from(eew.issued_at).round() as i64,
+		"estimated_intensity": o.estimated_intensity(eew),
+	})).collect();
+
+	json!(arrivals)
+}
+
+impl Template {
+
+	pub fn new(source: &str, language: Language) -> Result<Template, TemplateError>
+	{
+		let mut registry = build_registry(language);
+
+		registry.register_template_string(TEMPLATE_NAME, source).map_err(|e| {
+			let reason = e.reason().to_string();
+			match (e.line_no, e.column_no) {
+				(Some(l), Some(c)) => TemplateError::InvalidSyntax(format!("{}:{}: {}", l, c, reason)),
+				_ => TemplateError::InvalidSyntax(reason),
+			}
+		})?;
+
+		Ok(Template { source: source.to_owned(), language: language, registry: Arc::new(registry) })
+	}
+
+	// the names in `eew` are expected to be localized already
+	pub fn render(&self, eew: &EEW, prev_opt: Option<&EEW>, observers: &[Observer]) -> Result<String, TemplateError>
+	{
+		let context = self.build_context(eew, prev_opt, observers);
+		self.registry.render(TEMPLATE_NAME, &context).map_err(|e| TemplateError::RenderFailure(e.desc))
+	}
+
+	// every field of `EEW` and `EEWDetail` as serialized, plus:
+	// `phase`, `test`, `drill`, `last`, `updown` (an arrow or an empty string), `prev` (the previous EEW or null)
+	// `header`, `body` and `footer` of the default message and `observers` (see `build_arrivals`)
+	fn build_context(&self, eew: &EEW, prev_opt: Option<&EEW>, observers: &[Observer]) -> Value
+	{
+		let mut context = serde_json::to_value(eew).expect("EEW is always serializable");
+
+		let default = match self.language {
+			Language::Japanese => japanese::format_eew_short(eew, prev_opt),
+			Language::English => english::format_eew_short(eew, prev_opt),
+		};
+		let (header, body, footer) = match default {
+			Some((h, b, f)) => (json!(h), json!(b), json!(f)),
+			None => (Value::Null, Value::Null, Value::Null),
+		};
+
+		let updown = match japanese::compare_intensity(eew, prev_opt) {
+			Ordering::Greater => "↑",
+			Ordering::Less => "↓",
+			Ordering::Equal => "",
+		};
+
+		if let Some(obj) = context.as_object_mut() {
+			obj.insert("phase".to_owned(), json!(eew.get_eew_phase().map(json::format_phase)));
+			obj.insert("test".to_owned(), json!(eew.is_test()));
+			obj.insert("drill".to_owned(), json!(eew.is_drill()));
+			obj.insert("last".to_owned(), json!(eew.is_last()));
+			obj.insert("updown".to_owned(), json!(updown));
+			obj.insert("prev".to_owned(), json!(prev_opt));
+			obj.insert("header".to_owned(), header);
+			obj.insert("body".to_owned(), body);
+			obj.insert("footer".to_owned(), footer);
+			obj.insert("observers".to_owned(), build_arrivals(eew, observers));
+		}
+
+		context
+	}
+}
@@ -0,0 +1,115 @@
+use std::fs::File;
+use std::io::Read;
+
+// the JMA2001 travel time table (the `tjma2001` file distributed by JMA), whose lines are
+// `P time (s), phase, S time (s), phase, depth (km), epicentral distance (km)`
+#[derive(PartialEq, Debug)]
+pub struct TravelTimeTable {
+	// (depth, row) sorted by the depth; each row is sorted by the distance
+	rows: Vec<(f32, Vec<Point>)>,
+}
+
+// (distance, P time, S time)
+type Point = (f32, f32, f32);
+
+fn parse_line(line: &str) -> Option<(f32, f32, f32, f32)>
+{
+	let fields: Vec<&str> = line.split_whitespace().collect();
+	if fields.len() != 6 {
+		return None;
+	}
+
+	let p = fields[0].parse().ok()?;
+	let s = fields[2].parse().ok()?;
+	let depth = fields[4].parse().ok()?;
+	let distance = fields[5].parse().ok()?;
+	Some((depth, distance, p, s))
+}
+
+// the index `i` of the sorted `keys` with `keys[i] <= x <= keys[i + 1]` and the ratio of `x` between them
+fn locate<T, F>(keys: &[T], x: f32, key: F) -> Option<(usize, f32)> where F: Fn(&T) -> f32
+{
+	let (first, last) = (key(keys.first()?), key(keys.last()?));
+	if x < first || x > last {
+		return None;
+	}
+	if keys.len() == 1 {
+		return Some((0, 0.0));
+	}
+
+	let i = match keys.iter().position(|k| key(k) > x) {
+		Some(i) => i - 1,
+		None => keys.len() - 2,
+	};
+	let (lower, upper) = (key(&keys[i]), key(&keys[i + 1]));
+	Some((i, ((x - lower) / (upper - lower)).min(1.0)))
+}
+
+fn lerp(a: (f32, f32), b: (f32, f32), ratio: f32) -> (f32, f32)
+{
+	(a.0 + (b.0 - a.0) * ratio, a.1 + (b.1 - a.1) * ratio)
+}
+
+// (P time, S time) in the row interpolated linearly over the distance
+fn interpolate_row(row: &[Point], distance_km: f32) -> Option<(f32, f32)>
+{
+	let (i, ratio) = locate(row, distance_km, |r| r.0)?;
+	let lower = (row[i].1, row[i].2);
+	match row.get(i + 1) {
+		Some(upper) => Some(lerp(lower, (upper.1, upper.2), ratio)),
+		None => Some(lower),
+	}
+}
+
+impl TravelTimeTable {
+
+	// errors contain the line number of the invalid line
+	pub fn parse(text: &str) -> Result<TravelTimeTable, String>
+	{
+		let mut rows: Vec<(f32, Vec<Point>)> = Vec::new();
+
+		for (i, line) in text.lines().enumerate() {
+			if line.trim().is_empty() {
+				continue;
+			}
+
+			let (depth, distance, p, s) = parse_line(line)
+				.ok_or_else(|| format!("{}: expected `P time, phase, S time, phase, depth, distance`", i + 1))?;
+
+			match rows.iter_mut().find(|r| r.0 == depth) {
+				Some(r) => r.1.push((distance, p, s)),
+				None => rows.push((depth, vec![(distance, p, s)])),
+			}
+		}
+
+		if rows.is_empty() {
+			return Err("no travel times".to_owned());
+		}
+
+		rows.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+		for r in rows.iter_mut() {
+			r.1.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+		}
+
+		Ok(TravelTimeTable { rows: rows })
+	}
+
+	pub fn load(path: &str) -> Result<TravelTimeTable, String>
+	{
+		let mut text = String::new();
+		File::open(path).and_then(|mut f| f.read_to_string(&mut text)).map_err(|e| format!("{}: {}", path, e))?;
+		TravelTimeTable::parse(&text).map_err(|e| format!("{}:{}", path, e))
+	}
+
+	// (P time, S time) in seconds interpolated linearly over the depth and the epicentral distance;
+	// None out of the range of the table
+	pub fn travel_times(&self, depth_km: f32, distance_km: f32) -> Option<(f32, f32)>
+	{
+		let (i, ratio) = locate(&self.rows, depth_km, |r| r.0)?;
+		let shallower = interpolate_row(&self.rows[i].1, distance_km)?;
+		match self.rows.get(i + 1) {
+			Some(deeper) if ratio > 0.0 => Some(lerp(shallower, interpolate_row(&deeper.1, distance_km)?, ratio)),
+			_ => Some(shallower),
+		}
+	}
+}
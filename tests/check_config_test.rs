@@ -0,0 +1,223 @@
+extern crate tina;
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use tina::*;
+
+const AREA_CSV: &'static str = "100,石狩地方北部\n540,奈良県\n";
+const EPICENTER_CSV: &'static str = "540,奈良県\n";
+
+fn temp_dir(name: &str) -> PathBuf
+{
+	let dir = env::temp_dir().join(format!("tina_check_config_test_{}_{}", name, std::process::id()));
+	fs::create_dir_all(&dir).unwrap();
+	dir
+}
+
+// writes the config with the dictionaries and the required sections; `body` is appended as is
+fn write_config(name: &str, area_csv: &str, body: &str) -> String
+{
+	let dir = temp_dir(name);
+	let area = dir.join("area.csv");
+	let epicenter = dir.join("epicenter.csv");
+	let config = dir.join("tina.yaml");
+
+	fs::write(&area, area_csv).unwrap();
+	fs::write(&epicenter, EPICENTER_CSV).unwrap();
+	fs::write(&config, format!("\
+		path:\n  area: \"{}\"\n  epicenter: \"{}\"\n\
+		wni:\n  id: \"email@example.com\"\n  password: \"passw0rd\"\n\
+		log:\n  log_level: \"info\"\n{}",
+		area.display(), epicenter.display(), body)).unwrap();
+
+	config.to_string_lossy().into_owned()
+}
+
+fn webhook(cond: &str) -> String
+{
+	format!("webhook:\n  url: \"http://127.0.0.1:8080/\"\n  cond:\n{}", cond)
+}
+
+#[test]
+fn it_should_accept_the_example_config()
+{
+	let report = check_config("config/tina.yaml.example");
+
+	assert_eq!(report.errors, Vec::<String>::new());
+	assert_eq!(report.warnings, Vec::<String>::new());
+}
+
+#[test]
+fn it_should_accept_a_minimal_config()
+{
+	let report = check_config(&write_config("minimal", AREA_CSV, ""));
+
+	assert!(report.errors.is_empty() && report.warnings.is_empty(), "{:?}", report);
+}
+
+#[test]
+fn it_should_warn_contradicting_conditions()
+{
+	let cond = concat!(
+		"    - { first: true, succeeding: true }\n",
+		"    - { first: true, phase_changed: true }\n",
+		"    - { cancel: true, alert: true }\n",
+		"    - { cancel: true, magnitude_over: 5.0 }\n",
+		"    - { intensity_up: 1, intensity_down: 1 }\n",
+		"    - { areas: [] }\n",
+		"    - { succeeding: true, intensity_up: 1 }\n");
+	let report = check_config(&write_config("contradictions", AREA_CSV, &webhook(cond)));
+
+	assert_eq!(report.errors, Vec::<String>::new());
+	assert_eq!(report.warnings, vec!{
+		"webhook.cond[0]: never matches (`first` and `succeeding` are exclusive)",
+		"webhook.cond[1]: never matches (comparisons with the previous EEW never match the first EEW)",
+		"webhook.cond[2]: never matches (a cancel EEW is never an alert)",
+		"webhook.cond[3]: never matches (a cancel EEW has no details)",
+		"webhook.cond[4]: never matches (`intensity_up` and `intensity_down` are exclusive)",
+		"webhook.cond[5]: never matches (`areas` is empty)",
+	});
+}
+
+#[test]
+fn it_should_report_key_paths_of_nested_conditions()
+{
+	let cond = concat!(
+		"    - all:\n",
+		"      - not: { areas: [\"540\", \"奈良県\", \"unknown\"] }\n",
+		"      - any: []\n",
+		"    - observers: [\"nobody\"]\n",
+		"      distance_under_km: 100\n");
+	let report = check_config(&write_config("nested", AREA_CSV, &webhook(cond)));
+
+	assert_eq!(report.errors, Vec::<String>::new());
+	assert_eq!(report.warnings, vec!{
+		"webhook.cond[0].all[0].not.areas: unknown area `unknown`",
+		"webhook.cond[0].all[1].any: never matches (empty list)",
+		"webhook.cond[1].observers: unknown observer `nobody`",
+		"webhook.cond[1]: never matches (no observer for the observer conditions)",
+	});
+}
+
+#[test]
+fn it_should_report_negated_conditions_as_always_matching()
+{
+	let cond = concat!(
+		"    - not: { intensity_up: 11 }\n",
+		"    - not: { not: { intensity_down: 11 } }\n",
+		"    - all:\n",
+		"      - alert: true\n",
+		"      - not: { any: [] }\n");
+	let report = check_config(&write_config("negated", AREA_CSV, &webhook(cond)));
+
+	assert_eq!(report.errors, Vec::<String>::new());
+	assert_eq!(report.warnings, vec!{
+		"webhook.cond[0].not: always matches under `not` (the maximum intensity never changes by more than 10 classes)",
+		"webhook.cond[1].not.not: never matches (the maximum intensity never changes by more than 10 classes)",
+		"webhook.cond[2].all[1].not.any: always matches under `not` (empty list)",
+	});
+}
+
+#[test]
+fn it_should_report_key_paths_of_invalid_conditions()
+{
+	let cond = concat!(
+		"    - first: true\n",
+		"    - all:\n",
+		"      - not: { tset: true }\n");
+
+	match Config::load_config(&write_config("invalid_cond", AREA_CSV, &webhook(cond))) {
+		Err(ConfigLoadError::InvalidKeyValue(msg)) =>
+			assert!(msg.starts_with("webhook: cond[1].all[0].not: unknown field `tset`"), "{}", msg),
+		other => panic!("unexpected result: {:?}", other),
+	}
+}
+
+#[test]
+fn it_should_report_invalid_observers()
+{
+	let body = concat!(
+		"observers:\n",
+		"  - { name: \"a\", lat: 35.0, lon: 139.0 }\n",
+		"  - { name: \"a\", lat: 91.0, lon: 139.0, amplification: 0 }\n");
+	let report = check_config(&write_config("observers", AREA_CSV, body));
+
+	assert_eq!(report.errors, vec!{
+		"observers[1]: invalid position (91, 139)",
+		"observers[1]: amplification must be positive",
+		"observers[1]: duplicated name `a`",
+	});
+}
+
+#[test]
+fn it_should_report_invalid_destinations()
+{
+	let body = concat!(
+		"webhook:\n  url: \"ftp://example.com/\"\n",
+		"stream:\n  listen: \"not an address\"\n",
+		"exec:\n  - { program: \"true\", max_concurrency: 0 }\n");
+	let report = check_config(&write_config("destinations", AREA_CSV, &body[..body.find("exec").unwrap()]));
+
+	assert_eq!(report.errors, vec!{
+		"stream.listen: invalid address `not an address`",
+		"webhook.url: invalid url `ftp://example.com/`",
+	});
+
+	let report = check_config(&write_config("exec", AREA_CSV, &body[body.find("exec").unwrap()..]));
+
+	assert_eq!(report.errors, vec!{
+		"InvalidKeyValue(\"exec[0].max_concurrency: must be positive\")",
+	});
+}
+
+#[test]
+fn it_should_report_invalid_code_dictionaries()
+{
+	let duplicated = write_config("duplicated", "100,a\n101,b\n100,c\n", "");
+	let invalid = write_config("invalid", "100,a\n10a,b\n", "");
+	let short = write_config("short", "100,a\n1000,b\n", "");
+	let missing = write_config("missing", "100,a\n101\n", "");
+
+	match Config::load_config(&duplicated) {
+		Err(ConfigLoadError::DuplicatedCode(msg)) => assert!(msg.ends_with("area.csv:3: duplicated code `100`"), "{}", msg),
+		other => panic!("unexpected result: {:?}", other),
+	}
+	match Config::load_config(&invalid) {
+		Err(ConfigLoadError::InvalidCodeFormat(msg)) => assert!(msg.ends_with("area.csv:2: invalid code `10a`"), "{}", msg),
+		other => panic!("unexpected result: {:?}", other),
+	}
+	match Config::load_config(&short) {
+		Err(ConfigLoadError::InvalidCodeFormat(msg)) => assert!(msg.ends_with("area.csv:2: invalid code `1000`"), "{}", msg),
+		other => panic!("unexpected result: {:?}", other),
+	}
+	match Config::load_config(&missing) {
+		Err(ConfigLoadError::InvalidCodeFormat(msg)) => assert!(msg.contains("area.csv"), "{}", msg),
+		other => panic!("unexpected result: {:?}", other),
+	}
+}
+
+#[test]
+fn it_should_report_yaml_errors_with_positions()
+{
+	let syntax = write_config("syntax", AREA_CSV, "webhook: [\n");
+	let unknown_key = write_config("unknown_key", AREA_CSV, "webhook:\n  url: \"http://127.0.0.1/\"\n  retry: 3\n");
+
+	match Config::load_config(&syntax) {
+		Err(ConfigLoadError::InvalidYamlFormat(msg)) => assert!(msg.contains("line 10"), "{}", msg),
+		other => panic!("unexpected result: {:?}", other),
+	}
+	match Config::load_config(&unknown_key) {
+		Err(ConfigLoadError::InvalidKeyValue(msg)) => {
+			assert!(msg.starts_with("webhook: unknown field `retry`"), "{}", msg);
+			assert!(msg.ends_with("at line 11 column 3"), "{}", msg);
+		},
+		other => panic!("unexpected result: {:?}", other),
+	}
+
+	match Config::load_config("/nonexistent/tina.yaml") {
+		Err(ConfigLoadError::ConfigFileIo) => {},
+		other => panic!("unexpected result: {:?}", other),
+	}
+}
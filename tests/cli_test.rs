@@ -0,0 +1,67 @@
+use std::env;
+use std::fs;
+use std::process::{Command, Output};
+
+fn run(args: &[&str]) -> Output
+{
+	Command::new(env!("CARGO_BIN_EXE_tina")).args(args).env_remove("TINA_CONF_PATH").output().unwrap()
+}
+
+fn tina(args: &[&str]) -> (bool, String)
+{
+	let output = run(args);
+	(output.status.success(), String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[test]
+fn it_should_print_the_version()
+{
+	for flag in ["-v", "--version"].iter() {
+		let output = run(&[flag]);
+		let err = String::from_utf8_lossy(&output.stderr);
+		assert!(output.status.success());
+		assert!(output.stdout.is_empty());
+		assert!(err.starts_with("Tina - EEW Client (rev."), "{}", err);
+	}
+}
+
+#[test]
+fn it_should_accept_the_config_path_of_check_config()
+{
+	let (ok, out) = tina(&["check-config", "config/tina.yaml.example"]);
+	assert!(ok);
+	assert_eq!(out.lines().last(), Some("config/tina.yaml.example: 0 error(s), 0 warning(s)"));
+
+	let (ok, out) = tina(&["--config", "config/tina.yaml.example", "check-config"]);
+	assert!(ok);
+	assert!(out.starts_with("config/tina.yaml.example: "), "{}", out);
+
+	let (ok, out) = tina(&["check-config", "/nonexistent/tina.yaml"]);
+	assert!(! ok);
+	assert!(out.contains("/nonexistent/tina.yaml: 1 error(s)"), "{}", out);
+}
+
+#[test]
+fn it_should_report_test_eews_filtered_by_the_condition()
+{
+	let path = env::temp_dir().join(format!("tina_cli_test_send_{}.yaml", std::process::id()));
+	let path = path.to_str().unwrap();
+
+	fs::write(path, concat!(
+		"path:\n  area: \"config/area_code.csv\"\n  epicenter: \"config/epicenter_code.csv\"\n",
+		"wni:\n  id: \"email@example.com\"\n  password: \"passw0rd\"\n",
+		"log:\n  log_level: \"info\"\n",
+		"exec:\n",
+		"  - { program: \"true\", cond: [{ first: true }] }\n")).unwrap();
+
+	let (ok, out) = tina(&["--config", path, "send-test", "Exec (true)", "--interval", "0"]);
+	assert!(! ok);
+	assert_eq!(out.lines().filter(|l| l.ends_with(": filtered by the condition")).count(), 6, "{}", out);
+	assert!(! out.lines().any(|l| l.ends_with(": sent")), "{}", out);
+
+	let (ok, out) = tina(&["--config", path, "send-test", "Exec (true)", "--interval", "0", "--bypass-condition"]);
+	assert!(ok);
+	assert_eq!(out.lines().filter(|l| l.ends_with(": sent")).count(), 6, "{}", out);
+
+	fs::remove_file(path).unwrap();
+}
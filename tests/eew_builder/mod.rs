@@ -17,6 +17,7 @@ pub struct EEWBuilder {
 	magnitude: Option<Option<f32>>,
 	maximum_intensity: Option<Option<IntensityClass>>,
 	warning_status: Option<WarningStatus>,
+	area_info: Option<Vec<AreaEEW>>,
 }
 
 impl EEWBuilder {
@@ -27,7 +28,7 @@ impl EEWBuilder {
 			issue_pattern: None, kind: None, issued_at: None, occurred_at: None,
 			id: None, status: None, number: None, detail_none: false,
 			epicenter_name: None, epicenter: None, depth: None, magnitude: None,
-			maximum_intensity: None, warning_status: None,
+			maximum_intensity: None, warning_status: None, area_info: None,
 		}
 	}
 
@@ -115,9 +116,16 @@ impl EEWBuilder {
 		Self { warning_status: Some(warning_status), .. self }
 	}
 
+	#[allow(dead_code)]
+	pub fn area_info(self, area_info: Vec<AreaEEW>) -> Self
+	{
+		Self { area_info: Some(area_info), .. self }
+	}
+
 	pub fn build(self) -> EEW
 	{
 		let detail = EEWDetail {
+			epicenter_code: "540".to_owned(),
 			epicenter_name: self.epicenter_name.unwrap_or("奈良県".into()),
 			epicenter: self.epicenter.unwrap_or((34.4, 135.7)),
 			depth: self.depth.unwrap_or(Some(10.0)),
@@ -131,7 +139,7 @@ impl EEWBuilder {
 			intensity_change: IntensityChange::Unknown,
 			change_reason: ChangeReason::Unknown,
 			plum: false,
-			area_info: vec!{},
+			area_info: self.area_info.unwrap_or(vec!{}),
 		};
 
 		EEW {
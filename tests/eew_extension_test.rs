@@ -22,6 +22,7 @@ fn it_should_handle_phases()
 {
 	let eew_cancel_x = EEWBuilder::new().kind(Kind::Cancel).build();
 	let eew_cancel_y = EEWBuilder::new().kind(Kind::DrillCancel).build();
+	let eew_cancel_z = EEWBuilder::new().kind(Kind::Trial).issue_pattern(IssuePattern::Cancel).detail_none().build();
 
 	let eew_alert_x = EEWBuilder::new().warning_status(WarningStatus::Alert)
 		.issue_pattern(IssuePattern::HighAccuracy).build();
@@ -39,6 +40,7 @@ fn it_should_handle_phases()
 
 	assert_eq!(eew_cancel_x.get_eew_phase(), Some(EEWPhase::Cancel));
 	assert_eq!(eew_cancel_y.get_eew_phase(), Some(EEWPhase::Cancel));
+	assert_eq!(eew_cancel_z.get_eew_phase(), Some(EEWPhase::Cancel));
 	assert_eq!(eew_alert_x.get_eew_phase(), Some(EEWPhase::Alert));
 	assert_eq!(eew_alert_y.get_eew_phase(), Some(EEWPhase::Alert));
 	assert_eq!(eew_alert_z.get_eew_phase(), Some(EEWPhase::Alert));
@@ -0,0 +1,88 @@
+extern crate chrono;
+#[macro_use] extern crate serde_json;
+extern crate tina;
+
+use chrono::{Utc, TimeZone};
+use serde_json::Value;
+use tina::*;
+
+mod eew_builder;
+use eew_builder::*;
+
+fn build_eew_with_areas() -> EEW
+{
+	let mut eew = EEWBuilder::new().warning_status(WarningStatus::Alert).build();
+
+	eew.detail.as_mut().unwrap().area_info = vec! {
+		AreaEEW {
+			area_code: "540".to_owned(),
+			area_name: "奈良県".to_owned(),
+			minimum_intensity: IntensityClass::FiveLower,
+			maximum_intensity: Some(IntensityClass::FiveUpper),
+			reach_at: Some(Utc.ymd(2010, 1, 1).and_hms(0, 56, 10)),
+			warning_status: WarningStatus::Alert,
+			wave_status: WaveStatus::Unreached,
+		},
+		AreaEEW {
+			area_code: "521".to_owned(),
+			area_name: "大阪府南部".to_owned(),
+			minimum_intensity: IntensityClass::Four,
+			maximum_intensity: None,
+			reach_at: None,
+			warning_status: WarningStatus::Forecast,
+			wave_status: WaveStatus::Reached,
+		},
+	};
+
+	eew
+}
+
+#[test]
+fn it_should_round_trip_eew()
+{
+	let eew = build_eew_with_areas();
+	assert_eq!(EEW::from_json(&eew.to_json()), Ok(eew));
+}
+
+#[test]
+fn it_should_round_trip_eew_without_detail()
+{
+	let eew = EEWBuilder::new().kind(Kind::Cancel).detail_none().build();
+	assert_eq!(EEW::from_json(&eew.to_json()), Ok(eew));
+}
+
+#[test]
+fn it_should_use_stable_representation()
+{
+	let eew = build_eew_with_areas();
+	let value: Value = serde_json::from_str(&eew.to_json()).unwrap();
+
+	assert_eq!(value["version"], json!(EEW_JSON_VERSION));
+	assert_eq!(value["eew"]["issue_pattern"], json!("high_accuracy"));
+	assert_eq!(value["eew"]["issued_at"], json!("2010-01-01T01:00:02Z"));
+	assert_eq!(value["eew"]["detail"]["epicenter"], json!({ "lat": 34.4, "lon": 135.7 }));
+	assert_eq!(value["eew"]["detail"]["maximum_intensity"], json!("5-"));
+	assert_eq!(value["eew"]["detail"]["epicenter_accuracy"], json!("grid_search_low"));
+	assert_eq!(value["eew"]["detail"]["magnitude_accuracy"], json!("s_wave"));
+	assert_eq!(value["eew"]["detail"]["area_info"][0]["maximum_intensity"], json!("5+"));
+	assert_eq!(value["eew"]["detail"]["area_info"][1]["maximum_intensity"], json!(null));
+	assert_eq!(value["eew"]["detail"]["area_info"][1]["wave_status"], json!("reached"));
+}
+
+#[test]
+fn it_should_reject_unsupported_version()
+{
+	let eew = EEWBuilder::new().build();
+	let text = eew.to_json().replacen("\"version\":1", "\"version\":999", 1);
+
+	assert_eq!(EEW::from_json(&text), Err(EEWJsonError::UnsupportedVersion(999)));
+}
+
+#[test]
+fn it_should_reject_unknown_intensity()
+{
+	let eew = EEWBuilder::new().build();
+	let text = eew.to_json().replacen("\"5-\"", "\"8\"", 1);
+
+	assert_eq!(EEW::from_json(&text), Err(EEWJsonError::InvalidFormat));
+}
@@ -0,0 +1,193 @@
+extern crate chrono;
+extern crate serde_json;
+extern crate tina;
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, SocketAddr};
+use std::sync::Arc;
+use std::thread::sleep;
+use std::time::Duration;
+
+use serde_json::Value;
+use tina::*;
+
+mod eew_builder;
+use eew_builder::*;
+
+fn start() -> (Router<ConstantCondition>, SocketAddr)
+{
+	let stream = EEWStream::start("127.0.0.1:0", Duration::from_secs(300)).unwrap();
+	let addr = stream.local_addr();
+	(Router::new(stream, TRUE_CONDITION, "EEWStream"), addr)
+}
+
+fn emit(router: &mut Router<ConstantCondition>, eew: EEW)
+{
+	router.emit(&Arc::new(eew));
+	// waits for the destination thread
+	sleep(Duration::from_millis(200));
+}
+
+fn connect(addr: SocketAddr, request: &str) -> BufReader<TcpStream>
+{
+	let mut stream = TcpStream::connect(addr).unwrap();
+	stream.set_read_timeout(Some(Duration::from_millis(500))).unwrap();
+	stream.write_all(request.as_bytes()).unwrap();
+
+	let mut reader = BufReader::new(stream);
+	loop {
+		let mut line = String::new();
+		reader.read_line(&mut line).unwrap();
+		if line.trim().is_empty() {
+			return reader;
+		}
+	}
+}
+
+fn connect_sse(addr: SocketAddr, query: &str) -> BufReader<TcpStream>
+{
+	connect(addr, &format!("GET /stream{} HTTP/1.1\r\nAccept: text/event-stream\r\n\r\n", query))
+}
+
+// returns None on timeout
+fn next_sse(reader: &mut BufReader<TcpStream>) -> Option<Value>
+{
+	loop {
+		let mut line = String::new();
+		if reader.read_line(&mut line).is_err() {
+			return None;
+		}
+		if let Some(data) = line.strip_prefix("data: ") {
+			return Some(serde_json::from_str(data).unwrap());
+		}
+	}
+}
+
+#[test]
+fn it_should_push_eews_via_sse_with_replay()
+{
+	let (mut router, addr) = start();
+
+	emit(&mut router, EEWBuilder::new().id("A").number(1).build());
+	emit(&mut router, EEWBuilder::new().id("B").number(1).build());
+	emit(&mut router, EEWBuilder::new().id("B").number(2).status(Status::Last).build());
+
+	let mut reader = connect_sse(addr, "");
+
+	// B has already finished
+	let replayed = next_sse(&mut reader).unwrap();
+	assert_eq!(replayed["eew"]["id"], "A");
+	assert!(next_sse(&mut reader).is_none());
+
+	emit(&mut router, EEWBuilder::new().id("A").number(2).build());
+
+	let pushed = next_sse(&mut reader).unwrap();
+	assert_eq!(pushed["eew"]["number"], 2);
+	assert_eq!(pushed["prev"]["number"], 1);
+}
+
+#[test]
+fn it_should_filter_eews_by_query()
+{
+	let (mut router, addr) = start();
+
+	let mut reader = connect_sse(addr, "?alert=true&magnitude_over=5.0");
+
+	emit(&mut router, EEWBuilder::new().id("A").number(1).build());
+	emit(&mut router, EEWBuilder::new().id("A").number(2).warning_status(WarningStatus::Alert).build());
+
+	let pushed = next_sse(&mut reader).unwrap();
+	assert_eq!(pushed["eew"]["number"], 2);
+	assert!(next_sse(&mut reader).is_none());
+}
+
+#[test]
+fn it_should_reject_invalid_query()
+{
+	let (_router, addr) = start();
+
+	let mut stream = TcpStream::connect(addr).unwrap();
+	stream.write_all(b"GET /stream?unknown=1 HTTP/1.1\r\n\r\n").unwrap();
+
+	let mut response = String::new();
+	stream.read_to_string(&mut response).unwrap();
+	assert!(response.starts_with("HTTP/1.1 400 "));
+}
+
+// returns the reader and the response headers
+fn connect_websocket(addr: SocketAddr) -> (BufReader<TcpStream>, Vec<String>)
+{
+	let mut stream = TcpStream::connect(addr).unwrap();
+	stream.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+	stream.write_all(b"GET /stream HTTP/1.1\r\nHost: localhost\r\nUpgrade: websocket\r\n\
+		Connection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+		Sec-WebSocket-Version: 13\r\n\r\n").unwrap();
+
+	let mut reader = BufReader::new(stream);
+	let mut headers = Vec::new();
+	loop {
+		let mut line = String::new();
+		reader.read_line(&mut line).unwrap();
+		if line.trim().is_empty() {
+			break;
+		}
+		headers.push(line.trim().to_owned());
+	}
+
+	(reader, headers)
+}
+
+// a masked frame as sent by clients
+fn client_frame(opcode: u8, payload: &[u8]) -> Vec<u8>
+{
+	let mask = [0x12, 0x34, 0x56, 0x78];
+	let mut frame = vec![0x80 | opcode, 0x80 | payload.len() as u8];
+	frame.extend_from_slice(&mask);
+	frame.extend(payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+	frame
+}
+
+#[test]
+fn it_should_push_eews_via_websocket()
+{
+	let (mut router, addr) = start();
+	let (mut reader, headers) = connect_websocket(addr);
+
+	assert_eq!(headers[0], "HTTP/1.1 101 Switching Protocols");
+	// the example in RFC 6455
+	assert!(headers.contains(&"Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo=".to_owned()));
+
+	emit(&mut router, EEWBuilder::new().id("A").number(1).build());
+
+	let mut head = [0; 4];
+	reader.read_exact(&mut head).unwrap();
+	assert_eq!(head[0], 0x81);
+	assert_eq!(head[1], 126);
+
+	let len = ((head[2] as usize) << 8) | head[3] as usize;
+	let mut payload = vec![0; len];
+	reader.read_exact(&mut payload).unwrap();
+
+	let value: Value = serde_json::from_slice(&payload).unwrap();
+	assert_eq!(value["eew"]["id"], "A");
+}
+
+#[test]
+fn it_should_answer_websocket_ping_and_close()
+{
+	let (_router, addr) = start();
+	let (mut reader, _) = connect_websocket(addr);
+
+	reader.get_mut().write_all(&client_frame(0x9, b"hello")).unwrap();
+
+	let mut pong = [0; 7];
+	reader.read_exact(&mut pong).unwrap();
+	assert_eq!(&pong, b"\x8A\x05hello");
+
+	reader.get_mut().write_all(&client_frame(0x8, b"\x03\xE8bye")).unwrap();
+
+	// the status code is echoed and then the connection is closed
+	let mut rest = Vec::new();
+	reader.read_to_end(&mut rest).unwrap();
+	assert_eq!(rest, b"\x88\x02\x03\xE8");
+}
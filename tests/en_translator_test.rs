@@ -0,0 +1,132 @@
+extern crate chrono;
+extern crate tina;
+
+use std::sync::Arc;
+
+use tina::*;
+
+mod eew_builder;
+use eew_builder::*;
+
+
+fn en_names() -> Arc<NameDict>
+{
+	let mut names = NameDict::default();
+	names.epicenter.insert(*b"540", "Nara".to_owned());
+	names.area.insert(*b"540", "Nara".to_owned());
+	Arc::new(names)
+}
+
+#[test]
+fn it_should_format_cancel_eew()
+{
+	let eew = EEWBuilder::new()
+		.issue_pattern(IssuePattern::Cancel)
+		.kind(Kind::Cancel)
+		.detail_none()
+		.build();
+
+	let expected = "[Cancelled] --- | #10 NDXXXX".to_owned();
+
+	let result = en_format_eew_oneline(&eew, None);
+
+	assert_eq!(result, Some(expected));
+}
+
+#[test]
+fn it_should_format_high_accuracy_eew()
+{
+	let eew = EEWBuilder::new()
+		.build();
+
+	let expected =
+		"[Forecast] 奈良県 Shindo 5- M5.9 10km (N34.4/E135.7) at 09:55:59 JST | #10 NDXXXX".to_owned();
+
+	let result = en_format_eew_oneline(&eew, None);
+
+	assert_eq!(result, Some(expected));
+}
+
+#[test]
+fn it_should_format_last_alert_eew_with_intensity_up()
+{
+	let eew1 = EEWBuilder::new()
+		.maximum_intensity(Some(IntensityClass::Four))
+		.build();
+	let eew2 = EEWBuilder::new()
+		.maximum_intensity(Some(IntensityClass::SixUpper))
+		.warning_status(WarningStatus::Alert)
+		.status(Status::Last)
+		.build();
+
+	let expected =
+		"[Warning↑/Final] 奈良県 Shindo 6+ M5.9 10km (N34.4/E135.7) at 09:55:59 JST | #10 NDXXXX".to_owned();
+
+	let result = en_format_eew_oneline(&eew2, Some(&eew1));
+
+	assert_eq!(result, Some(expected));
+}
+
+#[test]
+fn it_should_format_drill_eew()
+{
+	let eew = EEWBuilder::new()
+		.issue_pattern(IssuePattern::IntensityOnly)
+		.kind(Kind::Drill)
+		.magnitude(None)
+		.maximum_intensity(None)
+		.build();
+
+	let expected =
+		"[Drill | Preliminary] 奈良県 Shindo unknown M--- 10km (N34.4/E135.7) at 09:55:59 JST | #10 NDXXXX".to_owned();
+
+	let result = en_format_eew_oneline(&eew, None);
+
+	assert_eq!(result, Some(expected));
+}
+
+#[test]
+fn it_should_translate_names_in_the_dictionary()
+{
+	let area = AreaEEW {
+		area_code: "540".to_owned(),
+		area_name: "奈良県".to_owned(),
+		minimum_intensity: IntensityClass::FiveLower,
+		maximum_intensity: None,
+		reach_at: None,
+		warning_status: WarningStatus::Forecast,
+		wave_status: WaveStatus::Unreached,
+	};
+	let eew = EEWBuilder::new().area_info(vec!{area}).build();
+
+	let translator = Translator::new(Language::English, en_names());
+	let localized = translator.localize(&eew);
+	let detail = localized.detail.as_ref().unwrap();
+
+	assert_eq!(detail.epicenter_name, "Nara");
+	assert_eq!(detail.area_info[0].area_name, "Nara");
+
+	let expected =
+		"[Forecast] Nara Shindo 5- M5.9 10km (N34.4/E135.7) at 09:55:59 JST | #10 NDXXXX".to_owned();
+
+	assert_eq!(translator.format_eew_oneline(&eew, None), Some(expected));
+}
+
+#[test]
+fn it_should_leave_unknown_names_in_japanese()
+{
+	let eew = EEWBuilder::new().epicenter_name("奈良県").build();
+	let translator = Translator::new(Language::English, Arc::new(NameDict::default()));
+
+	assert_eq!(translator.localize(&eew), eew);
+}
+
+#[test]
+fn it_should_format_in_japanese_by_default()
+{
+	let eew = EEWBuilder::new().build();
+	let translator = Translator::default();
+
+	assert_eq!(translator.language(), Language::Japanese);
+	assert_eq!(translator.format_eew_oneline(&eew, None), ja_format_eew_oneline(&eew, None));
+}
@@ -0,0 +1,104 @@
+extern crate chrono;
+extern crate tina;
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread::sleep;
+use std::time::Duration;
+
+use tina::*;
+
+mod eew_builder;
+use eew_builder::*;
+
+fn output_path(name: &str) -> PathBuf
+{
+	let path = env::temp_dir().join(format!("tina_exec_test_{}_{}", name, std::process::id()));
+	let _ = fs::remove_file(&path);
+	path
+}
+
+fn wait_for_output(path: &PathBuf) -> Option<String>
+{
+	for _ in 0..50 {
+		if let Ok(s) = fs::read_to_string(path) {
+			if s.ends_with('\n') {
+				return Some(s);
+			}
+		}
+		sleep(Duration::from_millis(100));
+	}
+	None
+}
+
+fn run(script: String, eew: EEW, timeout: Duration)
+{
+	let exec = Exec::new("sh".to_owned(), vec!["-c".to_owned(), script], timeout, 1);
+	let mut router = Router::new(exec, TRUE_CONDITION, "Exec");
+	router.emit(&Arc::new(eew));
+}
+
+#[test]
+fn it_should_pass_eew_as_environment_variables()
+{
+	let path = output_path("env");
+	let eew = EEWBuilder::new().id("ND20180101000000").number(3).warning_status(WarningStatus::Alert).build();
+	let script = format!("echo \"$TINA_EEW_ID $TINA_EEW_NUMBER $TINA_PHASE $TINA_MAGNITUDE $TINA_MAX_INTENSITY\" > {}",
+		path.display());
+
+	run(script, eew, Duration::from_secs(10));
+
+	assert_eq!(wait_for_output(&path), Some("ND20180101000000 3 alert 5.9 5-\n".to_owned()));
+}
+
+#[test]
+fn it_should_pass_eew_as_json_on_stdin()
+{
+	let path = output_path("stdin");
+	let eew = EEWBuilder::new().build();
+	let script = format!("cat > {0}.tmp; echo >> {0}.tmp; mv {0}.tmp {0}", path.display());
+
+	run(script, eew, Duration::from_secs(10));
+
+	let output = wait_for_output(&path).expect("output should be written");
+	assert!(output.starts_with('{'));
+	assert!(output.contains("\"id\":\"NDXXXX\""));
+}
+
+#[test]
+fn it_should_kill_timed_out_process()
+{
+	let path = output_path("timeout");
+	let eew = EEWBuilder::new().build();
+	let script = format!("sleep 3; echo done > {}", path.display());
+
+	run(script, eew, Duration::from_millis(200));
+
+	sleep(Duration::from_secs(4));
+	assert!(! path.exists());
+}
+
+#[test]
+fn it_should_kill_timed_out_process_not_reading_stdin()
+{
+	// the JSON is larger than the pipe buffer, so writing it blocks until the child is killed
+	let path = output_path("timeout_stdin");
+	let area = AreaEEW {
+		area_code: "540".to_owned(),
+		area_name: "x".repeat(100),
+		minimum_intensity: IntensityClass::Four,
+		maximum_intensity: None,
+		reach_at: None,
+		warning_status: WarningStatus::Alert,
+		wave_status: WaveStatus::Unreached,
+	};
+	let eew = EEWBuilder::new().area_info(vec![area; 1000]).build();
+	let script = format!("sleep 3; echo done > {}", path.display());
+
+	run(script, eew, Duration::from_millis(200));
+
+	sleep(Duration::from_secs(4));
+	assert!(! path.exists());
+}
@@ -0,0 +1,170 @@
+extern crate tina;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::sync_channel;
+use std::thread::sleep;
+use std::time::Duration;
+
+use tina::*;
+
+const WNI_ID: &'static str = "email@example.com";
+const WNI_PASSWORD: &'static str = "passw0rd";
+
+const TELEGRAM_1: &'static [u8] = b"37 03 00 130808165702 C11 130808165559 \
+	ND20130808165608 NCN006 JD////////////// JN/// \
+	540 N344 E1357 060 68 5+ RK33513 RT01/// RC21/// \
+	9999=";
+
+const TELEGRAM_2: &'static [u8] = b"37 03 00 130808165703 C11 130808165559 \
+	ND20130808165608 NCN007 JD////////////// JN/// \
+	540 N344 E1357 060 68 5+ RK33513 RT01/// RC21/// \
+	9999=";
+
+fn dicts() -> (HashMap<[u8; 3], String>, HashMap<[u8; 3], String>)
+{
+	let mut epicenter = HashMap::new();
+	epicenter.insert(b"540".to_owned(), "奈良県".to_owned());
+	(epicenter, HashMap::new())
+}
+
+fn telegram(data: &[u8], delay_ms: i64) -> FakeWniEvent
+{
+	FakeWniEvent::Telegram { data: data.to_vec(), delay_ms: delay_ms }
+}
+
+fn wait_until<F>(f: F) -> bool where F: Fn() -> bool
+{
+	wait_until_for(Duration::from_secs(1), f)
+}
+
+fn wait_until_for<F>(timeout: Duration, f: F) -> bool where F: Fn() -> bool
+{
+	for _ in 0..(timeout.as_millis() / 20) {
+		if f() {
+			return true;
+		}
+		sleep(Duration::from_millis(20));
+	}
+	false
+}
+
+fn build_wni(server: &FakeWniServer, password: &str) -> Wni
+{
+	Wni::new(WNI_ID.to_owned(), "40285072".to_owned(), password.to_owned(), server.server_list_url(), None)
+}
+
+#[test]
+fn it_should_retrieve_server_list()
+{
+	let server = FakeWniServer::start(WNI_ID, WNI_PASSWORD, vec!{}, None).unwrap();
+	let wni = build_wni(&server, WNI_PASSWORD);
+
+	assert_eq!(wni.retrieve_server(), Ok(server.fast_caster_addr().to_string()));
+	assert_eq!(server.stats().server_list_requests, 1);
+}
+
+#[test]
+fn it_should_fail_to_login_with_wrong_password()
+{
+	let server = FakeWniServer::start(WNI_ID, WNI_PASSWORD, vec!{}, None).unwrap();
+	let wni = build_wni(&server, "wrong password");
+
+	assert_eq!(wni.connect().err(), Some(WniError::Authentication));
+	assert_eq!(server.stats().failed_logins, 1);
+}
+
+#[test]
+fn it_should_respond_to_keep_alive()
+{
+	let (epicenter, area) = dicts();
+	let script = vec!{FakeWniEvent::KeepAlive, FakeWniEvent::KeepAlive, telegram(TELEGRAM_1, 0)};
+	let server = FakeWniServer::start(WNI_ID, WNI_PASSWORD, vec!{script}, None).unwrap();
+	let wni = build_wni(&server, WNI_PASSWORD);
+
+	let mut conn = wni.connect().unwrap();
+	let eew = conn.wait_for_telegram(&epicenter, &area).unwrap();
+
+	assert_eq!(eew.number, 6);
+	assert!(wait_until(|| server.stats().responses == 3));
+}
+
+#[test]
+fn it_should_detect_too_slow_connection()
+{
+	let (epicenter, area) = dicts();
+	let script = vec!{telegram(TELEGRAM_1, 5000), telegram(TELEGRAM_2, 0)};
+	let server = FakeWniServer::start(WNI_ID, WNI_PASSWORD, vec!{script}, None).unwrap();
+	let wni = build_wni(&server, WNI_PASSWORD);
+
+	let mut conn = wni.connect().unwrap();
+
+	assert_eq!(conn.wait_for_telegram(&epicenter, &area).map(|e| e.number), Ok(6));
+	assert_eq!(conn.wait_for_telegram(&epicenter, &area).map(|e| e.number), Err(WniError::TooSlow));
+}
+
+#[test]
+fn it_should_reconnect_after_connection_closed()
+{
+	let (epicenter, area) = dicts();
+	let scripts = vec!{
+		vec!{telegram(TELEGRAM_1, 0), FakeWniEvent::Close},
+		vec!{telegram(TELEGRAM_2, 0)},
+	};
+	let server = FakeWniServer::start(WNI_ID, WNI_PASSWORD, scripts, None).unwrap();
+	let wni = build_wni(&server, WNI_PASSWORD);
+
+	{
+		let mut conn = wni.connect().unwrap();
+		assert_eq!(conn.wait_for_telegram(&epicenter, &area).map(|e| e.number), Ok(6));
+		assert_eq!(conn.wait_for_telegram(&epicenter, &area).map(|e| e.number),
+			Err(WniError::ConnectionClosed));
+	}
+
+	let mut conn = wni.connect().unwrap();
+	assert_eq!(conn.wait_for_telegram(&epicenter, &area).map(|e| e.number), Ok(7));
+	assert_eq!(server.stats().connections, 2);
+}
+
+#[test]
+fn it_should_reconnect_in_connection_thread()
+{
+	let (epicenter, area) = dicts();
+	let scripts = vec!{
+		vec!{telegram(TELEGRAM_1, 0), FakeWniEvent::Close},
+		vec!{telegram(TELEGRAM_2, 0)},
+	};
+	let server = FakeWniServer::start(WNI_ID, WNI_PASSWORD, scripts, None).unwrap();
+	let state = Arc::new(Mutex::new(TinaState::new(EEWHistory::new(8), 1)));
+	let (tx, rx) = sync_channel(8);
+
+	spawn_conn_thread(0, build_wni(&server, WNI_PASSWORD), epicenter, area, tx, state.clone());
+
+	let timeout = Duration::from_secs(5);
+	assert_eq!(rx.recv_timeout(timeout).map(|e| e.number), Ok(6));
+	assert_eq!(rx.recv_timeout(timeout).map(|e| e.number), Ok(7));
+	assert_eq!(server.stats().connections, 2);
+
+	let status = state.lock().unwrap().connections[0].clone();
+	assert!(status.connected);
+	assert_eq!(status.server, Some(server.fast_caster_addr().to_string()));
+	assert!(status.last_received_at.is_some());
+}
+
+#[test]
+fn it_should_retry_failed_logins_in_connection_thread()
+{
+	let (epicenter, area) = dicts();
+	let server = FakeWniServer::start(WNI_ID, WNI_PASSWORD, vec!{}, None).unwrap();
+	let state = Arc::new(Mutex::new(TinaState::new(EEWHistory::new(8), 1)));
+	let (tx, _rx) = sync_channel(8);
+
+	spawn_conn_thread(0, build_wni(&server, "wrong password"), epicenter, area, tx, state.clone());
+
+	// the first retry waits for a second
+	assert!(wait_until_for(Duration::from_secs(3), || server.stats().failed_logins >= 2));
+
+	let status = state.lock().unwrap().connections[0].clone();
+	assert!(! status.connected);
+	assert_eq!(status.last_error, Some("Authentication".to_owned()));
+}
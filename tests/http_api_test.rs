@@ -0,0 +1,93 @@
+extern crate chrono;
+extern crate serde_json;
+extern crate tina;
+
+use std::io::{Read, Write};
+use std::net::{TcpStream, SocketAddr};
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+use tina::*;
+
+mod eew_builder;
+use eew_builder::*;
+
+fn get(addr: SocketAddr, path: &str) -> (u16, Value)
+{
+	let mut stream = TcpStream::connect(addr).unwrap();
+	write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+
+	let mut response = String::new();
+	stream.read_to_string(&mut response).unwrap();
+
+	let status = response[9..12].parse().unwrap();
+	let body = response.split_once("\r\n\r\n").unwrap().1;
+	(status, serde_json::from_str(body).unwrap())
+}
+
+fn start(connection_count: usize) -> (Arc<Mutex<TinaState>>, SocketAddr)
+{
+	let state = Arc::new(Mutex::new(TinaState::new(EEWHistory::new(8), connection_count)));
+	let api = HttpApi::start("127.0.0.1:0", state.clone()).unwrap();
+	(state, api.local_addr())
+}
+
+#[test]
+fn it_should_return_latest_eew()
+{
+	let (state, addr) = start(1);
+
+	assert_eq!(get(addr, "/eew/latest").0, 404);
+
+	{
+		let mut state = state.lock().unwrap();
+		state.history.append(EEWBuilder::new().id("A").number(1).build());
+		state.history.append(EEWBuilder::new().id("A").number(2).build());
+	}
+
+	let (status, body) = get(addr, "/eew/latest");
+	assert_eq!(status, 200);
+	assert_eq!(body["eew"]["id"], "A");
+	assert_eq!(body["eew"]["number"], 2);
+	assert_eq!(body["prev"]["number"], 1);
+}
+
+#[test]
+fn it_should_return_all_reports_of_event()
+{
+	let (state, addr) = start(1);
+
+	{
+		let mut state = state.lock().unwrap();
+		state.history.append(EEWBuilder::new().id("A").number(1).build());
+		state.history.append(EEWBuilder::new().id("B").number(1).build());
+		state.history.append(EEWBuilder::new().id("A").number(2).build());
+	}
+
+	let (status, body) = get(addr, "/eew/A");
+	assert_eq!(status, 200);
+	assert_eq!(body["id"], "A");
+	assert_eq!(body["reports"].as_array().unwrap().len(), 2);
+	assert_eq!(body["reports"][1]["number"], 2);
+
+	assert_eq!(get(addr, "/eew/C").0, 404);
+	assert_eq!(get(addr, "/unknown").0, 404);
+}
+
+#[test]
+fn it_should_report_connection_health()
+{
+	let (state, addr) = start(2);
+
+	let (status, body) = get(addr, "/health");
+	assert_eq!(status, 503);
+	assert_eq!(body["connections"].as_array().unwrap().len(), 2);
+
+	state.lock().unwrap().connections[1].set_connected("127.0.0.1:9999".to_owned());
+
+	let (status, body) = get(addr, "/health");
+	assert_eq!(status, 200);
+	assert_eq!(body["status"], "ok");
+	assert_eq!(body["connections"][0]["connected"], false);
+	assert_eq!(body["connections"][1]["server"], "127.0.0.1:9999");
+}
@@ -0,0 +1,76 @@
+extern crate chrono;
+extern crate tina;
+
+use tina::*;
+
+mod eew_builder;
+use eew_builder::*;
+
+
+fn assert_close(actual: f32, expected: f32)
+{
+	assert!((actual - expected).abs() < 0.01, "{} is not close to {}", actual, expected);
+}
+
+#[test]
+fn it_should_estimate_bedrock_pgv()
+{
+	assert_close(bedrock_pgv(7.0, 10.0, 30.0), 25.61);
+	assert_close(bedrock_pgv(5.9, 10.0, 30.0), 3.906);
+
+	assert!(bedrock_pgv(7.0, 10.0, 30.0) > bedrock_pgv(7.0, 10.0, 100.0));
+	assert!(bedrock_pgv(7.0, 10.0, 30.0) > bedrock_pgv(6.0, 10.0, 30.0));
+
+	// the magnitude saturates at Mw 8.3 and the fault distance is at least 3km
+	assert_eq!(moment_magnitude(9.0), 8.3);
+	assert_eq!(bedrock_pgv(6.0, 10.0, 0.0), bedrock_pgv(6.0, 10.0, 1.0));
+}
+
+#[test]
+fn it_should_convert_pgv_to_intensity()
+{
+	assert_close(instrumental_intensity(10.0), 4.4);
+	assert_close(instrumental_intensity(25.61), 5.102);
+
+	assert_close(site_amplification(600.0), 1.0);
+	assert_close(site_amplification(200.0), 2.550);
+}
+
+#[test]
+fn it_should_estimate_intensity_at_points()
+{
+	// M5.9 at the depth of 10km
+	let eew = EEWBuilder::new().build();
+	let epicenter = (34.4, 135.7);
+
+	assert_close(estimate_intensity(&eew, epicenter, 1.0).unwrap(), 4.785);
+	assert_close(estimate_intensity(&eew, epicenter, 2.0).unwrap(), 5.302);
+
+	assert_eq!(estimate_intensity_class(&eew, epicenter, 1.0), Some(IntensityClass::FiveLower));
+	assert_eq!(estimate_intensity_class(&eew, epicenter, 2.0), Some(IntensityClass::FiveUpper));
+	assert_eq!(estimate_intensity_class(&eew, (43.0, 141.3), 1.0), Some(IntensityClass::Zero));
+}
+
+#[test]
+fn it_should_not_estimate_intensity_without_magnitude()
+{
+	let intensity_only = EEWBuilder::new().magnitude(None).build();
+	let cancel = EEWBuilder::new().detail_none().build();
+
+	assert_eq!(estimate_intensity(&intensity_only, (34.4, 135.7), 1.0), None);
+	assert_eq!(estimate_intensity(&cancel, (34.4, 135.7), 1.0), None);
+}
+
+#[test]
+fn it_should_estimate_intensity_at_observers()
+{
+	let eew = EEWBuilder::new().build();
+	let observer = Observer { name: "office".to_owned(), lat: 34.4, lon: 135.7, amplification: 2.0, travel_times: None };
+
+	assert_eq!(observer.estimated_intensity(&eew), Some(IntensityClass::FiveUpper));
+
+	let template = Template::new("{{#each observers}}{{name}} {{intensity estimated_intensity}}{{/each}}",
+		Language::Japanese).unwrap();
+
+	assert_eq!(template.render(&eew, None, &[observer]), Ok("office 震度5強".to_owned()));
+}
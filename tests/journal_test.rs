@@ -0,0 +1,243 @@
+extern crate chrono;
+extern crate tina;
+extern crate serde_yaml;
+#[macro_use] extern crate serde_json;
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::mpsc::{channel, Sender};
+use std::thread::sleep;
+
+use chrono::Duration;
+use serde_json::Value;
+use tina::*;
+
+mod eew_builder;
+use eew_builder::*;
+
+// reports the numbers of emitted EEWs and their `prev` and the restored states
+struct Recorder {
+	emitted: Sender<(u32, Option<u32>)>,
+	restored: Sender<(String, Value)>,
+}
+
+impl Destination for Recorder {
+
+	fn emit(&mut self, latest: &EEW, prev: Option<&EEW>)
+	{
+		self.emitted.send((latest.number, prev.map(|p| p.number))).unwrap();
+	}
+
+	fn restore_state(&mut self, id: &str, state: Value)
+	{
+		self.restored.send((id.to_owned(), state)).unwrap();
+	}
+}
+
+// takes a while to send and keeps the number of the last EEW as the state
+struct Slow {
+	last: Option<u32>,
+}
+
+impl Destination for Slow {
+
+	fn emit(&mut self, latest: &EEW, _prev: Option<&EEW>)
+	{
+		sleep(std::time::Duration::from_millis(300));
+		self.last = Some(latest.number);
+	}
+
+	fn state(&self, _id: &str) -> Option<Value>
+	{
+		self.last.map(|n| json!(n))
+	}
+}
+
+fn journal_path(name: &str) -> PathBuf
+{
+	let path = env::temp_dir().join(format!("tina_journal_test_{}_{}", name, std::process::id()));
+	let _ = fs::remove_file(&path);
+	path
+}
+
+#[test]
+fn it_should_restore_eews_and_states()
+{
+	let path = journal_path("restore");
+	let path_str = path.to_str().unwrap();
+
+	let eew_1 = EEWBuilder::new().id("A").number(1).build();
+	let eew_2 = EEWBuilder::new().id("A").number(2).build();
+
+	{
+		let journal = Journal::open(path_str, Duration::seconds(3600)).unwrap();
+		journal.record_eew(&eew_1);
+		journal.record_state("Twitter", "A", json!(100));
+		journal.record_eew(&eew_2);
+		journal.record_state("Twitter", "A", json!(200));
+		journal.record_state("Slack", "A", json!({ "ts": "1.0" }));
+	}
+
+	let journal = Journal::open(path_str, Duration::seconds(3600)).unwrap();
+
+	assert_eq!(journal.eews(), vec!{eew_1.clone(), eew_2.clone()});
+	assert_eq!(journal.states("Twitter"), vec!{("A".to_owned(), json!(100)), ("A".to_owned(), json!(200))});
+	assert_eq!(journal.states("Slack"), vec!{("A".to_owned(), json!({ "ts": "1.0" }))});
+	assert_eq!(journal.states("Discord"), vec!{});
+
+	let mut his = EEWHistory::new(16);
+	for eew in journal.eews() {
+		his.restore(eew);
+	}
+
+	assert_eq!(his.get("A").map(|v| v.len()), Some(2));
+	assert_eq!(his.append(eew_2.clone()), None);
+
+	fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn it_should_skip_unchanged_states()
+{
+	let path = journal_path("unchanged");
+	let path_str = path.to_str().unwrap();
+
+	{
+		let journal = Journal::open(path_str, Duration::seconds(3600)).unwrap();
+		journal.record_state("Discord", "A", json!("msg"));
+		journal.record_state("Discord", "A", json!("msg"));
+		journal.record_state("Discord", "B", json!("msg"));
+	}
+
+	let journal = Journal::open(path_str, Duration::seconds(3600)).unwrap();
+	assert_eq!(journal.states("Discord").len(), 2);
+
+	fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn it_should_drop_expired_and_broken_entries()
+{
+	let path = journal_path("expired");
+	let path_str = path.to_str().unwrap();
+
+	{
+		let mut file = fs::File::create(&path).unwrap();
+		writeln!(file, "{}", json!({
+			"recorded_at": "2010-01-01T00:00:00Z",
+			"state": { "destination": "Twitter", "id": "A", "value": 1 },
+		})).unwrap();
+		writeln!(file, "{{\"recorded_at\": \"20").unwrap();
+	}
+
+	let journal = Journal::open(path_str, Duration::seconds(3600)).unwrap();
+	assert_eq!(journal.states("Twitter"), vec!{});
+	journal.record_state("Twitter", "B", json!(2));
+	drop(journal);
+
+	let content = fs::read_to_string(&path).unwrap();
+	assert_eq!(content.lines().count(), 1);
+
+	fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn it_should_compact_on_writer_thread()
+{
+	let path = journal_path("compact");
+	let path_str = path.to_str().unwrap();
+
+	{
+		let journal = Journal::open(path_str, Duration::milliseconds(50)).unwrap();
+		journal.record_state("Twitter", "A", json!(1));
+		journal.record_state("Twitter", "B", json!(2));
+		sleep(std::time::Duration::from_millis(100));
+		journal.record_state("Twitter", "C", json!(3));
+	}
+
+	// the writes are finished when the journal is dropped
+	let content = fs::read_to_string(&path).unwrap();
+	assert_eq!(content.lines().count(), 1);
+	assert!(content.contains("\"C\""));
+
+	fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn it_should_restore_routers()
+{
+	let journal = Journal::in_memory(Duration::seconds(3600));
+	journal.record_eew(&EEWBuilder::new().id("A").number(1).build());
+	journal.record_state("Recorder", "A", json!(100));
+	journal.record_state("Other", "A", json!(200));
+
+	let (emitted_tx, emitted) = channel();
+	let (restored_tx, restored) = channel();
+	let first = build_yaml_condition(serde_yaml::from_str("- first: true").unwrap(), &[]);
+
+	{
+		let recorder = Recorder { emitted: emitted_tx.clone(), restored: restored_tx.clone() };
+		let mut router = Router::with_journal(recorder, first, "Recorder", Some(journal.clone()));
+		// A has already been sent before the restart
+		router.emit(&Arc::new(EEWBuilder::new().id("A").number(2).build()));
+		router.emit(&Arc::new(EEWBuilder::new().id("B").number(1).build()));
+	}
+
+	{
+		let recorder = Recorder { emitted: emitted_tx, restored: restored_tx };
+		let mut router = Router::with_journal(recorder, TRUE_CONDITION, "Recorder", Some(journal.clone()));
+		router.emit(&Arc::new(EEWBuilder::new().id("A").number(3).build()));
+	}
+
+	assert_eq!(emitted.iter().collect::<Vec<_>>(), vec!{(1, None), (3, Some(1))});
+	assert_eq!(restored.iter().collect::<Vec<_>>(), vec!{("A".to_owned(), json!(100)), ("A".to_owned(), json!(100))});
+}
+
+#[test]
+fn it_should_restore_states_of_destinations()
+{
+	let mut twitter = Twitter::new("ck".to_owned(), "cs".to_owned(), "ak".to_owned(), "as".to_owned(),
+		true, false, Translator::default());
+	let mut slack = Slack::with_bot_token("xoxb-token".to_owned(), "C1234".to_owned(), false, Translator::default());
+	let mut discord = Discord::build("https://discord.com/api/webhooks/0/token", false, Translator::default()).unwrap();
+
+	let tweet = json!(1234567890);
+	let thread = json!({ "msg": { "channel": "C1234", "ts": "1.0" }, "phase": null });
+	let message = json!("1234");
+
+	twitter.restore_state("A", tweet.clone());
+	slack.restore_state("A", thread.clone());
+	discord.restore_state("A", message.clone());
+
+	assert_eq!(twitter.state("A"), Some(tweet));
+	assert_eq!(slack.state("A"), Some(thread));
+	assert_eq!(discord.state("A"), Some(message));
+	assert_eq!(twitter.state("B"), None);
+}
+
+#[test]
+fn it_should_restore_states_recorded_by_predecessors()
+{
+	let journal = Journal::in_memory(Duration::seconds(3600));
+	let (emitted_tx, emitted) = channel();
+	let (restored_tx, restored) = channel();
+
+	let eew = EEWBuilder::new().id("A").number(1).build();
+	let mut old = Router::with_journal(Slow { last: None }, TRUE_CONDITION, "Recorder", Some(journal.clone()));
+	journal.record_eew(&eew);
+	old.emit(&Arc::new(eew));
+	// the old router is still sending the EEW
+	let handle = old.close();
+
+	{
+		let recorder = Recorder { emitted: emitted_tx, restored: restored_tx };
+		let mut router = Router::with_predecessor(recorder, TRUE_CONDITION, "Recorder", Some(journal.clone()), handle);
+		router.emit(&Arc::new(EEWBuilder::new().id("A").number(2).build()));
+	}
+
+	assert_eq!(restored.iter().collect::<Vec<_>>(), vec!{("A".to_owned(), json!(1))});
+	assert_eq!(emitted.iter().collect::<Vec<_>>(), vec!{(2, Some(1))});
+}
@@ -0,0 +1,49 @@
+extern crate chrono;
+#[macro_use] extern crate serde_json;
+extern crate tina;
+
+use tina::*;
+
+mod eew_builder;
+use eew_builder::*;
+
+#[test]
+fn it_should_format_eew_as_json()
+{
+	let eew = EEWBuilder::new().warning_status(WarningStatus::Alert).build();
+	let result = format_eew_json(&eew, None);
+
+	assert_eq!(result["version"], json!(1));
+	assert_eq!(result["phase"], json!("alert"));
+	assert_eq!(result["eew"]["id"], json!("NDXXXX"));
+	assert_eq!(result["eew"]["number"], json!(10));
+	assert_eq!(result["eew"]["kind"], json!("normal"));
+	assert_eq!(result["eew"]["issued_at"], json!("2010-01-01T01:00:02Z"));
+	assert_eq!(result["eew"]["detail"]["epicenter_name"], json!("奈良県"));
+	assert_eq!(result["eew"]["detail"]["maximum_intensity"], json!("5-"));
+	assert_eq!(result["eew"]["detail"]["depth"], json!(10.0));
+	assert_eq!(result["eew"]["detail"]["area_info"], json!([]));
+	assert_eq!(result["prev"], json!(null));
+}
+
+#[test]
+fn it_should_format_cancel_eew_as_json()
+{
+	let eew = EEWBuilder::new().kind(Kind::Cancel).detail_none().build();
+	let result = format_eew_json(&eew, None);
+
+	assert_eq!(result["phase"], json!("cancel"));
+	assert_eq!(result["eew"]["detail"], json!(null));
+}
+
+#[test]
+fn it_should_include_previous_eew_summary()
+{
+	let prev = EEWBuilder::new().number(1).maximum_intensity(Some(IntensityClass::Three)).build();
+	let eew = EEWBuilder::new().number(2).build();
+	let result = format_eew_json(&eew, Some(&prev));
+
+	assert_eq!(result["prev"]["number"], json!(1));
+	assert_eq!(result["prev"]["phase"], json!("forecast"));
+	assert_eq!(result["prev"]["maximum_intensity"], json!("3"));
+}
@@ -0,0 +1,70 @@
+extern crate chrono;
+#[macro_use] extern crate slog;
+extern crate tina;
+
+use std::sync::Arc;
+
+use slog::{Logger, Discard};
+use tina::*;
+
+mod eew_builder;
+use eew_builder::*;
+
+#[test]
+fn it_should_render_declared_metrics()
+{
+	let out = Metrics::render();
+
+	assert!(out.contains("# TYPE tina_wni_connected_threads gauge\n"));
+	assert!(out.contains("# TYPE tina_telegram_delay_seconds histogram\n"));
+	assert!(out.contains("# TYPE tina_router_eews_total counter\n"));
+}
+
+#[test]
+fn it_should_count_router_results()
+{
+	let eew = Arc::new(EEWBuilder::new().build());
+
+	{
+		let mut filtered = Router::new(Logging::new(Logger::root(Discard, o!())), FALSE_CONDITION, "metrics-filtered");
+		let mut sent = Router::new(Logging::new(Logger::root(Discard, o!())), TRUE_CONDITION, "metrics-sent");
+		filtered.emit(&eew);
+		sent.emit(&eew);
+		sent.emit(&eew);
+	}
+
+	let out = Metrics::render();
+
+	assert!(out.contains("tina_router_eews_total{router=\"metrics-filtered\",result=\"filtered\"} 1\n"));
+	assert!(out.contains("tina_router_eews_total{router=\"metrics-sent\",result=\"sent\"} 2\n"));
+}
+
+#[test]
+fn it_should_observe_histogram()
+{
+	Metrics::observe("tina_telegram_delay_seconds", &[], 0.2);
+	Metrics::observe("tina_telegram_delay_seconds", &[], 3.0);
+
+	let out = Metrics::render();
+
+	assert!(out.contains("tina_telegram_delay_seconds_bucket{le=\"0.1\"} 0\n"));
+	assert!(out.contains("tina_telegram_delay_seconds_bucket{le=\"0.25\"} 1\n"));
+	assert!(out.contains("tina_telegram_delay_seconds_bucket{le=\"5\"} 2\n"));
+	assert!(out.contains("tina_telegram_delay_seconds_bucket{le=\"+Inf\"} 2\n"));
+	assert!(out.contains("tina_telegram_delay_seconds_count 2\n"));
+}
+
+#[test]
+fn it_should_count_deduplicated_eews()
+{
+	let mut his = EEWHistory::new(4);
+	let eew = EEWBuilder::new().id("metrics").build();
+
+	his.append(eew.clone());
+	his.append(eew);
+
+	let out = Metrics::render();
+
+	assert!(out.contains("tina_eews_total{result=\"accepted\"}"));
+	assert!(out.contains("tina_eews_total{result=\"deduplicated\"}"));
+}
@@ -0,0 +1,203 @@
+extern crate chrono;
+extern crate tina;
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::sync::mpsc::{channel, Sender};
+use std::thread::{sleep, spawn};
+use std::time::{Duration, Instant};
+
+use tina::*;
+
+mod eew_builder;
+use eew_builder::*;
+
+#[derive(Debug, PartialEq)]
+enum Packet {
+	Connect { client_id: String, will_topic: Option<String> },
+	Publish { topic: String, payload: String, qos: u8, retain: bool },
+	Disconnect,
+}
+
+fn read_packet(stream: &mut TcpStream) -> Option<(u8, Vec<u8>)>
+{
+	let mut b = [0; 1];
+	stream.read_exact(&mut b).ok()?;
+	let header = b[0];
+
+	let (mut len, mut multiplier) = (0, 1);
+	loop {
+		stream.read_exact(&mut b).ok()?;
+		len += (b[0] & 0x7F) as usize * multiplier;
+		multiplier *= 128;
+		if b[0] & 0x80 == 0 {
+			break;
+		}
+	}
+
+	let mut body = vec![0; len];
+	stream.read_exact(&mut body).ok()?;
+	Some((header, body))
+}
+
+fn read_string(body: &[u8], pos: &mut usize) -> String
+{
+	let len = ((body[*pos] as usize) << 8) | body[*pos + 1] as usize;
+	let s = String::from_utf8(body[(*pos + 2)..(*pos + 2 + len)].to_vec()).unwrap();
+	*pos += 2 + len;
+	s
+}
+
+// a broker accepting a single connection and acknowledging every message
+fn serve(listener: TcpListener, tx: Sender<Packet>)
+{
+	let mut stream = listener.accept().unwrap().0;
+
+	while let Some((header, body)) = read_packet(&mut stream) {
+		match header & 0xF0 {
+			0x10 => {
+				let mut pos = 10;
+				let flags = body[7];
+				let client_id = read_string(&body, &mut pos);
+				let will_topic = match flags & 0x04 {
+					0 => None,
+					_ => Some(read_string(&body, &mut pos)),
+				};
+				tx.send(Packet::Connect { client_id: client_id, will_topic: will_topic }).unwrap();
+				stream.write_all(&[0x20, 0x02, 0x00, 0x00]).unwrap();
+			},
+			0x30 => {
+				let qos = (header >> 1) & 0x03;
+				let mut pos = 0;
+				let topic = read_string(&body, &mut pos);
+				if qos > 0 {
+					stream.write_all(&[0x40, 0x02, body[pos], body[pos + 1]]).unwrap();
+					pos += 2;
+				}
+				let payload = String::from_utf8(body[pos..].to_vec()).unwrap();
+				tx.send(Packet::Publish { topic: topic, payload: payload, qos: qos, retain: header & 0x01 == 1 })
+					.unwrap();
+			},
+			0xE0 => {
+				tx.send(Packet::Disconnect).unwrap();
+				return;
+			},
+			_ => {},
+		}
+	}
+}
+
+fn published(packet: Packet) -> (String, String, bool)
+{
+	match packet {
+		Packet::Publish { topic, payload, qos, retain } => {
+			assert_eq!(qos, 1);
+			(topic, payload, retain)
+		},
+		p => panic!("unexpected packet: {:?}", p),
+	}
+}
+
+#[test]
+fn it_should_publish_eew_to_broker()
+{
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let port = listener.local_addr().unwrap().port();
+	let (tx, rx) = channel();
+	spawn(move || serve(listener, tx));
+
+	let mqtt = Mqtt::new("127.0.0.1".to_owned(), port, "tina-test".to_owned(), None,
+		"tina/".to_owned(), 1, true);
+	assert!(mqtt.is_valid());
+
+	{
+		let mut router = Router::new(mqtt, TRUE_CONDITION, "Mqtt");
+		router.emit(&Arc::new(EEWBuilder::new().id("ND20180101000000").build()));
+	}
+
+	let timeout = Duration::from_secs(5);
+
+	assert_eq!(rx.recv_timeout(timeout).unwrap(), Packet::Connect {
+		client_id: "tina-test".to_owned(), will_topic: Some("tina/status".to_owned()) });
+	assert_eq!(published(rx.recv_timeout(timeout).unwrap()),
+		("tina/status".to_owned(), "online".to_owned(), true));
+
+	let (topic, payload, retain) = published(rx.recv_timeout(timeout).unwrap());
+	assert_eq!(topic, "tina/eew/ND20180101000000");
+	assert!(payload.contains("\"id\":\"ND20180101000000\""));
+	assert!(retain);
+
+	let (topic, latest_payload, _) = published(rx.recv_timeout(timeout).unwrap());
+	assert_eq!(topic, "tina/eew/latest");
+	assert_eq!(latest_payload, payload);
+
+	assert_eq!(published(rx.recv_timeout(timeout).unwrap()),
+		("tina/status".to_owned(), "offline".to_owned(), true));
+	assert_eq!(rx.recv_timeout(timeout).unwrap(), Packet::Disconnect);
+}
+
+#[test]
+fn it_should_connect_before_the_first_eew()
+{
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let port = listener.local_addr().unwrap().port();
+	let (tx, rx) = channel();
+	spawn(move || serve(listener, tx));
+
+	let mqtt = Mqtt::new("127.0.0.1".to_owned(), port, "tina-test".to_owned(), None,
+		"tina/".to_owned(), 1, true);
+	let _router = Router::new(mqtt, TRUE_CONDITION, "Mqtt");
+
+	// e.g. right after reloading the config, the status is back online without waiting for an EEW
+	let timeout = Duration::from_secs(5);
+	assert!(matches!(rx.recv_timeout(timeout).unwrap(), Packet::Connect { .. }));
+	assert_eq!(published(rx.recv_timeout(timeout).unwrap()),
+		("tina/status".to_owned(), "online".to_owned(), true));
+}
+
+#[test]
+fn it_should_reject_invalid_settings()
+{
+	let wildcard = Mqtt::new("localhost".to_owned(), 1883, "tina".to_owned(), None, "tina/#".to_owned(), 1, false);
+	let qos = Mqtt::new("localhost".to_owned(), 1883, "tina".to_owned(), None, "tina".to_owned(), 3, false);
+
+	assert!(! wildcard.is_valid());
+	assert!(! qos.is_valid());
+}
+
+#[test]
+fn it_should_keep_eews_while_broker_is_down()
+{
+	// reserve a port and close it so that connecting is refused
+	let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+	let mqtt = Mqtt::new("127.0.0.1".to_owned(), port, "tina-test".to_owned(), None,
+		"tina/".to_owned(), 1, false);
+	let mut router = Router::new(mqtt, TRUE_CONDITION, "Mqtt");
+
+	// emitting must not sleep through the reconnect backoff
+	let started = Instant::now();
+	router.emit(&Arc::new(EEWBuilder::new().id("ND20180101000000").number(1).build()));
+	router.emit(&Arc::new(EEWBuilder::new().id("ND20180101000000").number(2).build()));
+	sleep(Duration::from_millis(500));
+	assert!(started.elapsed() < Duration::from_secs(2));
+
+	let listener = TcpListener::bind(("127.0.0.1", port)).unwrap();
+	let (tx, rx) = channel();
+	spawn(move || serve(listener, tx));
+
+	// the pending EEWs are published after the backoff
+	sleep(Duration::from_secs(2));
+	router.emit(&Arc::new(EEWBuilder::new().id("ND20180101000000").number(3).build()));
+
+	let timeout = Duration::from_secs(5);
+	assert!(matches!(rx.recv_timeout(timeout).unwrap(), Packet::Connect { .. }));
+	assert_eq!(published(rx.recv_timeout(timeout).unwrap()).0, "tina/status");
+
+	for number in 1..4 {
+		let (topic, payload, _) = published(rx.recv_timeout(timeout).unwrap());
+		assert_eq!(topic, "tina/eew/ND20180101000000");
+		assert!(payload.contains(&format!("\"number\":{}", number)), "{}", payload);
+		assert_eq!(published(rx.recv_timeout(timeout).unwrap()).0, "tina/eew/latest");
+	}
+}
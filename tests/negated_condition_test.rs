@@ -0,0 +1,32 @@
+extern crate chrono;
+extern crate tina;
+
+use tina::*;
+
+mod eew_builder;
+use eew_builder::*;
+
+fn check<C>(cond: C) -> bool
+	where C: Condition
+{
+	let eew = EEWBuilder::new().build();
+	cond.is_satisfied(&eew, None)
+}
+
+#[test]
+fn it_should_negate_condition()
+{
+	assert_eq!(check(NegatedCondition(TRUE_CONDITION)), false);
+	assert_eq!(check(NegatedCondition(FALSE_CONDITION)), true);
+}
+
+#[test]
+fn it_should_handle_nested_boxed_conditions()
+{
+	// not (false or (true and not false))
+	let inner: Vec<Box<dyn Condition>> = vec!{Box::new(TRUE_CONDITION), Box::new(NegatedCondition(FALSE_CONDITION))};
+	let outer: Vec<Box<dyn Condition>> = vec!{Box::new(FALSE_CONDITION), Box::new(ConjunctiveCondition::from(inner))};
+	let cond = NegatedCondition(DisjunctiveCondition::from(outer));
+
+	assert_eq!(check(cond), false);
+}
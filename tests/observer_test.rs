@@ -0,0 +1,150 @@
+extern crate chrono;
+extern crate tina;
+
+use std::sync::Arc;
+
+use chrono::{Utc, TimeZone};
+
+use tina::*;
+
+mod eew_builder;
+use eew_builder::*;
+
+
+fn observer(name: &str, lat: f32, lon: f32) -> Observer
+{
+	Observer { name: name.to_owned(), lat: lat, lon: lon, amplification: 1.0, travel_times: None }
+}
+
+#[test]
+fn it_should_compute_epicentral_distances()
+{
+	let tokyo = (35.681, 139.767);
+	let osaka = (34.702, 135.495);
+
+	let d = epicentral_distance_km(tokyo, osaka);
+	assert!((d - 403.0).abs() < 2.0, "{}", d);
+
+	assert_eq!(epicentral_distance_km(tokyo, tokyo), 0.0);
+	assert!((epicentral_distance_km((0.0, 0.0), (0.0, 1.0)) - 111.2).abs() < 0.1);
+}
+
+#[test]
+fn it_should_estimate_arrival_times()
+{
+	// the epicenter is exactly 70km north of the observer at the depth of 0km
+	let eew = EEWBuilder::new()
+		.epicenter((34.0 + 70.0 / 111.195, 135.0))
+		.depth(Some(0.0))
+		.occurred_at(Utc.ymd(2010, 1, 1).and_hms(0, 0, 0))
+		.issued_at(Utc.ymd(2010, 1, 1).and_hms(0, 0, 5))
+		.build();
+
+	let arrival = observer("office", 34.0, 135.0).arrival(&eew).unwrap();
+
+	assert_eq!(arrival.observer, "office");
+	assert!((arrival.distance_km - 70.0).abs() < 0.1);
+	assert_eq!(arrival.p_wave_at, Utc.ymd(2010, 1, 1).and_hms(0, 0, 10));
+	assert_eq!(arrival.s_wave_at, Utc.ymd(2010, 1, 1).and_hms_milli(0, 0, 17, 500));
+	assert_eq!(arrival.p_wave_secs_from(eew.issued_at), 5.0);
+	assert_eq!(arrival.s_wave_secs_from(eew.issued_at), 12.5);
+}
+
+#[test]
+fn it_should_include_the_depth_in_the_distance()
+{
+	let eew = EEWBuilder::new().epicenter((34.0, 135.0)).depth(Some(40.0)).build();
+	let arrival = observer("office", 34.0, 135.0).arrival(&eew).unwrap();
+
+	assert_eq!(arrival.epicentral_distance_km, 0.0);
+	assert_eq!(arrival.distance_km, 40.0);
+}
+
+#[test]
+fn it_should_not_estimate_arrivals_of_cancel_eews()
+{
+	let eew = EEWBuilder::new().detail_none().build();
+
+	assert_eq!(observer("office", 34.0, 135.0).arrival(&eew), None);
+}
+
+#[test]
+fn it_should_render_arrivals_in_templates()
+{
+	let eew = EEWBuilder::new()
+		.epicenter((34.0 + 70.0 / 111.195, 135.0))
+		.depth(Some(0.0))
+		.occurred_at(Utc.ymd(2010, 1, 1).and_hms(0, 0, 0))
+		.issued_at(Utc.ymd(2010, 1, 1).and_hms(0, 0, 5))
+		.build();
+	let template = Template::new("{{#each observers}}{{name}}: {{distance_km}}km, \
+		S-wave in {{s_wave_secs}}s ({{time s_wave_at}}){{/each}}", Language::English).unwrap();
+
+	let result = template.render(&eew, None, &[observer("Osaka office", 34.0, 135.0)]);
+
+	assert_eq!(result, Ok("Osaka office: 70km, S-wave in 13s (09:00:17)".to_owned()));
+}
+
+// a small grid in the layout of `tjma2001` (P time, phase, S time, phase, depth, distance)
+const TABLE: &'static str = concat!(
+	" 14.00 P  24.00 S  10  90\n",
+	" 15.50 P  26.80 S  10 100\n",
+	" 17.00 P  29.60 S  10 110\n",
+	" 15.00 P  26.00 S  20  90\n",
+	" 16.50 P  28.60 S  20 100\n",
+	" 18.00 P  31.20 S  20 110\n");
+
+fn assert_times(actual: Option<(f32, f32)>, expected: (f32, f32))
+{
+	let (p, s) = actual.unwrap();
+	assert!((p - expected.0).abs() < 1e-4 && (s - expected.1).abs() < 1e-4, "{:?}", (p, s));
+}
+
+#[test]
+fn it_should_interpolate_travel_times()
+{
+	let table = TravelTimeTable::parse(TABLE).unwrap();
+
+	// the values in the table
+	assert_times(table.travel_times(10.0, 100.0), (15.5, 26.8));
+	assert_times(table.travel_times(20.0, 110.0), (18.0, 31.2));
+	// over the distance, the depth and both
+	assert_times(table.travel_times(10.0, 95.0), (14.75, 25.4));
+	assert_times(table.travel_times(15.0, 100.0), (16.0, 27.7));
+	assert_times(table.travel_times(12.5, 105.0), (16.5, 28.625));
+
+	assert_eq!(table.travel_times(5.0, 100.0), None);
+	assert_eq!(table.travel_times(10.0, 120.0), None);
+}
+
+#[test]
+fn it_should_reject_invalid_travel_time_tables()
+{
+	assert_eq!(TravelTimeTable::parse(" 14.00 P  24.00 S  10  90\n 15.50 P\n"),
+		Err("2: expected `P time, phase, S time, phase, depth, distance`".to_owned()));
+	assert_eq!(TravelTimeTable::parse(""), Err("no travel times".to_owned()));
+}
+
+#[test]
+fn it_should_estimate_arrival_times_by_the_table()
+{
+	// the epicenter is 100km north of the observer at the depth of 10km
+	let eew = EEWBuilder::new()
+		.epicenter((34.0 + 100.0 / 111.195, 135.0))
+		.depth(Some(10.0))
+		.occurred_at(Utc.ymd(2010, 1, 1).and_hms(0, 0, 0))
+		.build();
+
+	let mut office = observer("office", 34.0, 135.0);
+	office.travel_times = Some(Arc::new(TravelTimeTable::parse(TABLE).unwrap()));
+	let arrival = office.arrival(&eew).unwrap();
+
+	assert_eq!(arrival.p_wave_at, Utc.ymd(2010, 1, 1).and_hms_milli(0, 0, 15, 500));
+	assert_eq!(arrival.s_wave_at, Utc.ymd(2010, 1, 1).and_hms_milli(0, 0, 26, 800));
+
+	// out of the table
+	let eew = EEWBuilder::new().epicenter((34.0 + 70.0 / 111.195, 135.0)).depth(Some(0.0))
+		.occurred_at(Utc.ymd(2010, 1, 1).and_hms(0, 0, 0)).build();
+	let arrival = office.arrival(&eew).unwrap();
+	assert_eq!(arrival.p_wave_at, Utc.ymd(2010, 1, 1).and_hms(0, 0, 10));
+}
@@ -71,6 +71,7 @@ fn it_should_parse_normal_eew_01()
 		status: Status::Normal,
 		number: 1,
 		detail: Some(EEWDetail {
+			epicenter_code: "287".to_owned(),
 			epicenter_name: "宮城県沖".to_owned(),
 			epicenter: (38.0, 142.0),
 			depth: Some(10.0),
@@ -109,6 +110,7 @@ fn it_should_parse_normal_eew_02()
 		status: Status::Normal,
 		number: 6,
 		detail: Some(EEWDetail {
+			epicenter_code: "540".to_owned(),
 			epicenter_name: "奈良県".to_owned(),
 			epicenter: (34.4, 135.7),
 			depth: Some(60.0),
@@ -141,9 +143,10 @@ fn it_should_parse_ebi_part()
 		592 S0404 165703 10 591 S0403 ////// 11 301 S5-// 010101 01 \
 		9999=";
 
-	let make_areaeew = |area_name: &str, minimum_intensity: f32, maximum_intensity: Option<f32>,
+	let make_areaeew = |area_code: &str, area_name: &str, minimum_intensity: f32, maximum_intensity: Option<f32>,
 		reach_at: Option<DateTime<Utc>>, warning: bool, reached: bool|
 		-> AreaEEW { AreaEEW {
+			area_code: area_code.to_owned(),
 			area_name: area_name.to_owned(),
 			minimum_intensity: IntensityClass::new(minimum_intensity),
 			maximum_intensity: maximum_intensity.map(|i| IntensityClass::new(i)),
@@ -164,6 +167,7 @@ fn it_should_parse_ebi_part()
 		status: Status::Normal,
 		number: 6,
 		detail: Some(EEWDetail {
+			epicenter_code: "540".to_owned(),
 			epicenter_name: "奈良県".to_owned(),
 			epicenter: (34.4, 135.7),
 			depth: Some(60.0),
@@ -178,18 +182,18 @@ fn it_should_parse_ebi_part()
 			change_reason: ChangeReason::Magnitude,
 			plum: false,
 			area_info: vec! {
-				make_areaeew("大阪府南部", 5.25, Some(5.25), None, true, true),
-				make_areaeew("奈良県", 4.75, Some(5.25), None, true, true),
-				make_areaeew("京都府南部", 4.75, Some(5.25), None, true, true),
-				make_areaeew("和歌山県北部", 4.75, Some(4.75), None, true, true),
-				make_areaeew("和歌山県南部", 4.0, Some(4.75), None, true, true),
-				make_areaeew("兵庫県淡路島", 4.0, Some(4.75), None, true, true),
-				make_areaeew("石川県加賀", 4.0, Some(4.0), None, true, true),
-				make_areaeew("愛媛県東予", 4.0, Some(4.0), None, true, true),
-				make_areaeew("鳥取県西部", 4.0, Some(4.0), Some(date.and_hms(7, 57, 3)), true, false),
-				make_areaeew("広島県南西部", 4.0, Some(4.0), Some(date.and_hms(7, 57, 3)), true, false),
-				make_areaeew("広島県南東部", 3.0, Some(4.0), None, true, true),
-				make_areaeew("茨城県南部", 4.75, None, Some(date.and_hms(16, 1, 1)), false, true),
+				make_areaeew("521", "大阪府南部", 5.25, Some(5.25), None, true, true),
+				make_areaeew("540", "奈良県", 4.75, Some(5.25), None, true, true),
+				make_areaeew("511", "京都府南部", 4.75, Some(5.25), None, true, true),
+				make_areaeew("550", "和歌山県北部", 4.75, Some(4.75), None, true, true),
+				make_areaeew("551", "和歌山県南部", 4.0, Some(4.75), None, true, true),
+				make_areaeew("535", "兵庫県淡路島", 4.0, Some(4.75), None, true, true),
+				make_areaeew("391", "石川県加賀", 4.0, Some(4.0), None, true, true),
+				make_areaeew("620", "愛媛県東予", 4.0, Some(4.0), None, true, true),
+				make_areaeew("563", "鳥取県西部", 4.0, Some(4.0), Some(date.and_hms(7, 57, 3)), true, false),
+				make_areaeew("592", "広島県南西部", 4.0, Some(4.0), Some(date.and_hms(7, 57, 3)), true, false),
+				make_areaeew("591", "広島県南東部", 3.0, Some(4.0), None, true, true),
+				make_areaeew("301", "茨城県南部", 4.75, None, Some(date.and_hms(16, 1, 1)), false, true),
 			},
 		}),
 	};
@@ -215,6 +219,7 @@ fn it_should_parse_eew_with_unknown_values()
 		status: Status::Normal,
 		number: 1,
 		detail: Some(EEWDetail {
+			epicenter_code: "432".to_owned(),
 			epicenter_name: "岐阜県美濃中西部".to_owned(),
 			epicenter: (35.4, 136.9),
 			depth: None,
@@ -234,3 +239,23 @@ fn it_should_parse_eew_with_unknown_values()
 
 	assert_eq!(parse(telegram), Ok(expected));
 }
+
+#[test]
+fn it_should_fall_back_to_codes_for_unknown_codes()
+{
+	let telegram = b"37 03 00 130808165702 C11 130808165559 \
+		ND20130808165608 NCN006 JD////////////// JN/// \
+		999 N344 E1357 060 68 5+ RK33513 RT01/// RC21/// \
+		EBI 521 S5+5+ ////// 11 998 S5+5- ////// 11 \
+		9999=";
+
+	let detail = parse(telegram).expect("unknown codes should not be fatal").detail.unwrap();
+
+	assert_eq!(detail.epicenter_code, "999");
+	assert_eq!(detail.epicenter_name, "999");
+	assert_eq!(detail.area_info.len(), 2);
+	assert_eq!(detail.area_info[0].area_code, "521");
+	assert_eq!(detail.area_info[0].area_name, "大阪府南部");
+	assert_eq!(detail.area_info[1].area_code, "998");
+	assert_eq!(detail.area_info[1].area_name, "998");
+}
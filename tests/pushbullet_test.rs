@@ -0,0 +1,18 @@
+extern crate reqwest;
+extern crate tina;
+
+use reqwest::StatusCode;
+use tina::*;
+
+
+#[test]
+fn it_should_map_statuses()
+{
+	assert_eq!(PushBulletError::check_status(StatusCode::OK), Ok(()));
+	assert_eq!(PushBulletError::check_status(StatusCode::BAD_REQUEST), Err(PushBulletError::InvalidRequest));
+	assert_eq!(PushBulletError::check_status(StatusCode::UNAUTHORIZED), Err(PushBulletError::Unauthorized));
+	assert_eq!(PushBulletError::check_status(StatusCode::FORBIDDEN), Err(PushBulletError::Unauthorized));
+	assert_eq!(PushBulletError::check_status(StatusCode::TOO_MANY_REQUESTS), Err(PushBulletError::RateLimitExceeded));
+	assert_eq!(PushBulletError::check_status(StatusCode::INTERNAL_SERVER_ERROR),
+		Err(PushBulletError::Unknown("unknown status: 500 Internal Server Error".to_owned())));
+}
@@ -0,0 +1,149 @@
+#[macro_use] extern crate slog;
+extern crate chrono;
+extern crate serde_json;
+extern crate tina;
+
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread::spawn;
+use std::time::Duration;
+
+use serde_json::Value;
+use slog::{Discard, Logger};
+use tina::*;
+
+mod eew_builder;
+use eew_builder::*;
+
+// an HTTP server receiving webhook requests; the bodies are sent on the channel
+fn serve(listener: TcpListener, tx: Sender<Value>)
+{
+	for stream in listener.incoming().filter_map(|s| s.ok()) {
+		let tx = tx.clone();
+		spawn(move || {
+			let mut reader = BufReader::new(stream.try_clone().unwrap());
+			let mut stream = stream;
+			loop {
+				let mut len = 0;
+				loop {
+					let mut line = String::new();
+					if reader.read_line(&mut line).unwrap_or(0) == 0 {
+						return;
+					}
+					if line.trim().is_empty() {
+						break;
+					}
+					if let Some(v) = line.to_lowercase().strip_prefix("content-length:") {
+						len = v.trim().parse().unwrap();
+					}
+				}
+				let mut body = vec![0; len];
+				reader.read_exact(&mut body).unwrap();
+				stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n").unwrap();
+				tx.send(serde_json::from_slice(&body).unwrap()).unwrap();
+			}
+		});
+	}
+}
+
+fn start_webhook_server() -> (u16, Receiver<Value>)
+{
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let port = listener.local_addr().unwrap().port();
+	let (tx, rx) = channel();
+	spawn(move || serve(listener, tx));
+	(port, rx)
+}
+
+fn write_config(path: &str, webhook_port: u16, stream_port: u16, auto_reload: bool)
+{
+	fs::write(path, format!(concat!(
+		"path:\n  area: \"config/area_code.csv\"\n  epicenter: \"config/epicenter_code.csv\"\n",
+		"wni:\n  id: \"email@example.com\"\n  password: \"passw0rd\"\n",
+		"log:\n  log_level: \"info\"\n",
+		"auto_reload: {}\n",
+		"webhook:\n  url: \"http://127.0.0.1:{}/\"\n",
+		"stream:\n  listen: \"127.0.0.1:{}\"\n"),
+		auto_reload, webhook_port, stream_port)).unwrap();
+}
+
+fn dispatch(socks: &mut Vec<Box<dyn Routing>>, journal: &Journal, eew: EEW)
+{
+	let eew = Arc::new(eew);
+	for s in socks.iter_mut() {
+		s.emit(&eew);
+	}
+	journal.record_eew(&eew);
+}
+
+#[test]
+fn it_should_reload_routers_keeping_their_states()
+{
+	let path = env::temp_dir().join(format!("tina_reload_test_{}.yaml", std::process::id()));
+	let path = path.to_str().unwrap();
+
+	let (webhook_port, rx) = start_webhook_server();
+	let stream_port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+	write_config(path, webhook_port, stream_port, false);
+
+	let logger = Logger::root(Discard, o!());
+	let journal = Journal::in_memory(chrono::Duration::hours(1));
+	let conf = Config::load_config(path).unwrap();
+	let mut socks = build_routers(&conf, logger.clone(), Some(&journal));
+
+	dispatch(&mut socks, &journal, EEWBuilder::new().id("A").number(1).build());
+	let first = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+	assert_eq!(first["eew"]["number"], 1);
+	assert_eq!(first["prev"], Value::Null);
+
+	// the stream listens on the same address again and the new auto_reload is returned
+	write_config(path, webhook_port, stream_port, true);
+	let reloaded = reload_routers(path, &mut socks, &logger, &journal).unwrap();
+	assert!(reloaded.auto_reload);
+
+	let names: Vec<_> = socks.iter().map(|s| s.name().to_owned()).collect();
+	assert_eq!(names, vec!{"Log", "EEWStream", "Webhook"});
+	TcpStream::connect(("127.0.0.1", stream_port)).unwrap();
+
+	// the prev buffer is restored
+	dispatch(&mut socks, &journal, EEWBuilder::new().id("A").number(2).build());
+	let second = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+	assert_eq!(second["eew"]["number"], 2);
+	assert_eq!(second["prev"]["number"], 1);
+
+	// the current routers are kept if the config is invalid
+	fs::write(path, "wni: [\n").unwrap();
+	assert!(reload_routers(path, &mut socks, &logger, &journal).is_none());
+	assert_eq!(socks.len(), 3);
+
+	fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn it_should_name_exec_routers_uniquely()
+{
+	let path = env::temp_dir().join(format!("tina_reload_test_exec_{}.yaml", std::process::id()));
+	let path = path.to_str().unwrap();
+
+	fs::write(path, concat!(
+		"path:\n  area: \"config/area_code.csv\"\n  epicenter: \"config/epicenter_code.csv\"\n",
+		"wni:\n  id: \"email@example.com\"\n  password: \"passw0rd\"\n",
+		"log:\n  log_level: \"info\"\n",
+		"exec:\n",
+		"  - { program: \"true\" }\n",
+		"  - { program: \"true\", args: [\"a\"] }\n",
+		"  - { program: \"false\" }\n",
+		"  - { program: \"true\", args: [\"b\"] }\n")).unwrap();
+
+	let conf = Config::load_config(path).unwrap();
+	let socks = build_routers(&conf, Logger::root(Discard, o!()), None);
+
+	let names: Vec<_> = socks.iter().map(|s| s.name().to_owned()).collect();
+	assert_eq!(names, vec!{"Log", "Exec (true)", "Exec (true) #2", "Exec (false)", "Exec (true) #3"});
+
+	fs::remove_file(path).unwrap();
+}
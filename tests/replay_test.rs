@@ -0,0 +1,82 @@
+extern crate tina;
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use tina::*;
+
+const TELEGRAM_1: &'static [u8] = b"37 03 00 130808165702 C11 130808165559 \
+	ND20130808165608 NCN006 JD////////////// JN/// \
+	540 N344 E1357 060 68 5+ RK33513 RT01/// RC21/// \
+	9999=";
+
+const TELEGRAM_2: &'static [u8] = b"37 03 00 130808165703 C11 130808165559 \
+	ND20130808165608 NCN007 JD////////////// JN/// \
+	540 N344 E1357 060 68 5+ RK33513 RT01/// RC21/// \
+	9999=";
+
+fn build_log(telegrams: &[&[u8]]) -> Vec<u8>
+{
+	let mut log = Vec::new();
+
+	for t in telegrams.iter() {
+		log.extend_from_slice(b"Aug 08 16:57:02.123 INFO [127.0.0.1:8080] X-WNI-ID: Data\n");
+		log.extend_from_slice(b"Aug 08 16:57:02.123 INFO [127.0.0.1:8080] \\x5c\x02\n");
+		log.extend_from_slice(t);
+		log.extend_from_slice(b"\n\x03\n");
+	}
+
+	log
+}
+
+fn dicts() -> (HashMap<[u8; 3], String>, HashMap<[u8; 3], String>)
+{
+	let mut epicenter = HashMap::new();
+	epicenter.insert(b"540".to_owned(), "奈良県".to_owned());
+	(epicenter, HashMap::new())
+}
+
+#[test]
+fn it_should_extract_telegrams_from_wni_log()
+{
+	let log = build_log(&[TELEGRAM_1, TELEGRAM_2]);
+	assert_eq!(extract_telegrams(&log), vec!{TELEGRAM_1.to_vec(), TELEGRAM_2.to_vec()});
+}
+
+#[test]
+fn it_should_extract_telegrams_from_raw_file()
+{
+	let mut data = Vec::new();
+	data.extend_from_slice(TELEGRAM_1);
+	data.extend_from_slice(b"\n\n");
+	data.extend_from_slice(TELEGRAM_2);
+	data.extend_from_slice(b"\n");
+
+	assert_eq!(extract_telegrams(&data), vec!{TELEGRAM_1.to_vec(), TELEGRAM_2.to_vec()});
+}
+
+#[test]
+fn it_should_play_telegrams_in_order()
+{
+	let (epicenter, area) = dicts();
+	let replay = Replay::new(vec!{TELEGRAM_1.to_vec(), TELEGRAM_2.to_vec()}, None);
+	let mut numbers = Vec::new();
+
+	replay.play(&epicenter, &area, |r| numbers.push(r.map(|eew| eew.number)));
+
+	assert_eq!(numbers, vec!{Ok(6), Ok(7)});
+}
+
+#[test]
+fn it_should_honor_original_timing_with_speed_factor()
+{
+	let (epicenter, area) = dicts();
+	let replay = Replay::new(vec!{TELEGRAM_1.to_vec(), TELEGRAM_2.to_vec()}, Some(4.0));
+
+	let started = Instant::now();
+	replay.play(&epicenter, &area, |_| {});
+	let elapsed = started.elapsed();
+
+	assert!(elapsed >= Duration::from_millis(250));
+	assert!(elapsed < Duration::from_millis(1000));
+}
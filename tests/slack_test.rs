@@ -0,0 +1,81 @@
+extern crate chrono;
+extern crate reqwest;
+#[macro_use] extern crate serde_json;
+extern crate tina;
+
+use reqwest::StatusCode;
+use tina::*;
+
+mod eew_builder;
+use eew_builder::*;
+
+
+fn thread(phase: Option<EEWPhase>) -> SlackThread
+{
+	SlackThread { msg: SlackMessage { channel: "C123".to_owned(), ts: "1500000000.000100".to_owned() }, phase: phase }
+}
+
+#[test]
+fn it_should_post_the_first_eew_of_an_id()
+{
+	let eew = EEWBuilder::new().build();
+
+	assert_eq!(SlackBotAction::decide(None, &eew), SlackBotAction::Post);
+}
+
+#[test]
+fn it_should_update_the_message_without_replies_in_the_same_phase()
+{
+	let eew = EEWBuilder::new().build();
+	let t = thread(eew.get_eew_phase());
+
+	assert_eq!(SlackBotAction::decide(Some(&t), &eew), SlackBotAction::Update { msg: t.msg.clone(), reply: false });
+}
+
+#[test]
+fn it_should_reply_in_the_thread_on_phase_changes_and_the_last_eew()
+{
+	let t = thread(Some(EEWPhase::Forecast));
+	let alert = EEWBuilder::new().warning_status(WarningStatus::Alert).build();
+	let last = EEWBuilder::new().status(Status::Last).build();
+	let cancel = EEWBuilder::new().issue_pattern(IssuePattern::Cancel).kind(Kind::Cancel).detail_none().build();
+
+	assert_eq!(last.get_eew_phase(), Some(EEWPhase::Forecast));
+
+	assert_eq!(SlackBotAction::decide(Some(&t), &alert), SlackBotAction::Update { msg: t.msg.clone(), reply: true });
+	assert_eq!(SlackBotAction::decide(Some(&t), &last), SlackBotAction::Update { msg: t.msg.clone(), reply: true });
+	assert_eq!(SlackBotAction::decide(Some(&t), &cancel), SlackBotAction::Update { msg: t.msg.clone(), reply: true });
+}
+
+#[test]
+fn it_should_map_web_api_results()
+{
+	let ok = json!({ "ok": true, "channel": "C123", "ts": "1500000000.000100" });
+	let no_ts = json!({ "ok": true, "channel": "C123" });
+	let lost = json!({ "ok": false, "error": "message_not_found" });
+	let unknown = json!({ "ok": false });
+
+	assert_eq!(SlackError::check_api_result(ok.clone()), Ok(ok.clone()));
+	assert_eq!(SlackMessage::from_api_result(&ok), Ok(thread(None).msg));
+	assert_eq!(SlackMessage::from_api_result(&no_ts), Err(SlackError::InvalidResponse));
+
+	assert_eq!(SlackError::check_api_result(lost), Err(SlackError::Api("message_not_found".to_owned())));
+	assert_eq!(SlackError::check_api_result(unknown), Err(SlackError::Api("unknown".to_owned())));
+}
+
+#[test]
+fn it_should_repost_only_lost_messages()
+{
+	assert!(SlackError::Api("message_not_found".to_owned()).is_message_lost());
+	assert!(! SlackError::Api("channel_not_found".to_owned()).is_message_lost());
+	assert!(! SlackError::Network.is_message_lost());
+	assert!(! SlackError::Rejected.is_message_lost());
+}
+
+#[test]
+fn it_should_map_webhook_statuses()
+{
+	assert_eq!(SlackError::check_webhook_status(StatusCode::OK), Ok(()));
+	assert_eq!(SlackError::check_webhook_status(StatusCode::NOT_FOUND), Err(SlackError::Rejected));
+	assert_eq!(SlackError::check_webhook_status(StatusCode::INTERNAL_SERVER_ERROR), Err(SlackError::Rejected));
+}
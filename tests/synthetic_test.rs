@@ -0,0 +1,43 @@
+extern crate tina;
+extern crate chrono;
+
+use chrono::{Utc, TimeZone};
+
+use tina::*;
+
+#[test]
+fn it_should_build_a_sequence_of_test_eews()
+{
+	let occurred_at = Utc.ymd(2026, 10, 18).and_hms(9, 0, 0);
+	let eews = synthetic_eews(occurred_at, Kind::Trial);
+
+	assert_eq!(eews.len(), 6);
+	assert!(eews.iter().all(|e| e.is_test() && ! e.is_drill()));
+
+	let phases: Vec<Option<EEWPhase>> = eews.iter().map(|e| e.get_eew_phase()).collect();
+	assert_eq!(phases, vec!{
+		Some(EEWPhase::Forecast), Some(EEWPhase::Forecast), Some(EEWPhase::Alert), Some(EEWPhase::Alert),
+		Some(EEWPhase::Forecast), Some(EEWPhase::Cancel),
+	});
+
+	assert_eq!(eews[0].id, "ND20261018090000");
+	assert!(eews[0].is_succeeded_by(&eews[1]));
+	assert!(eews[1].is_succeeded_by(&eews[2]));
+	assert!(eews[2].is_succeeded_by(&eews[3]));
+	assert!(eews[3].is_last());
+
+	let max_intensity = |e: &EEW| e.detail.as_ref().and_then(|d| d.maximum_intensity);
+	assert!(max_intensity(&eews[1]) > max_intensity(&eews[0]));
+
+	assert_ne!(eews[4].id, eews[0].id);
+	assert!(eews[4].is_succeeded_by(&eews[5]));
+	assert!(eews[5].detail.is_none());
+}
+
+#[test]
+fn it_should_build_reference_eews()
+{
+	let eews = synthetic_eews(Utc::now(), Kind::Reference);
+
+	assert!(eews.iter().all(|e| e.kind == Kind::Reference && e.is_test()));
+}
@@ -0,0 +1,107 @@
+extern crate tina;
+
+use std::collections::HashMap;
+use std::thread::sleep;
+use std::time::Duration;
+
+use tina::*;
+
+const PART_1: &'static [u8] = b"37 03 00 130808165702 C12 130808165559 \
+	ND20130808165608 NCN006 JD////////////// JN/// \
+	540 N344 E1357 060 68 5+ RK33513 RT01/// RC21/// \
+	EBI 521 S5+5+ ////// 11 540 S5+5- ////// 11 \
+	9999=";
+
+const PART_2: &'static [u8] = b"37 03 00 130808165702 C22 130808165559 \
+	ND20130808165608 NCN006 JD////////////// JN/// \
+	540 N344 E1357 060 68 5+ RK33513 RT01/// RC21/// \
+	EBI 511 S5+5- ////// 11 \
+	9999=";
+
+const JOINED: &'static [u8] = b"37 03 00 130808165702 C11 130808165559 \
+	ND20130808165608 NCN006 JD////////////// JN/// \
+	540 N344 E1357 060 68 5+ RK33513 RT01/// RC21/// \
+	EBI 521 S5+5+ ////// 11 540 S5+5- ////// 11 511 S5+5- ////// 11 \
+	9999=";
+
+fn parse(telegram: &[u8]) -> Result<EEW, JMAFormatParseError>
+{
+	let mut epicenter = HashMap::new();
+	let mut area = HashMap::new();
+
+	epicenter.insert(b"540".to_owned(), "奈良県".to_owned());
+	area.insert(b"521".to_owned(), "大阪府南部".to_owned());
+	area.insert(b"540".to_owned(), "奈良県".to_owned());
+	area.insert(b"511".to_owned(), "京都府南部".to_owned());
+
+	parse_jma_format(telegram, &epicenter, &area)
+}
+
+#[test]
+fn it_should_pass_unsplit_telegrams_through()
+{
+	let mut asm = TelegramAssembler::new(Duration::from_secs(10));
+
+	assert_eq!(asm.push(JOINED), Some(JOINED.to_vec()));
+	assert_eq!(asm.pending_count(), 0);
+}
+
+#[test]
+fn it_should_join_split_telegrams()
+{
+	let mut asm = TelegramAssembler::new(Duration::from_secs(10));
+
+	assert_eq!(asm.push(PART_1), None);
+	assert_eq!(asm.pending_count(), 1);
+	assert_eq!(asm.push(PART_2), Some(JOINED.to_vec()));
+	assert_eq!(asm.pending_count(), 0);
+}
+
+#[test]
+fn it_should_join_parts_received_in_reverse_order()
+{
+	let mut asm = TelegramAssembler::new(Duration::from_secs(10));
+
+	assert_eq!(asm.push(PART_2), None);
+	assert_eq!(asm.push(PART_1), Some(JOINED.to_vec()));
+}
+
+#[test]
+fn it_should_deliver_all_areas_of_joined_telegram()
+{
+	let mut asm = TelegramAssembler::new(Duration::from_secs(10));
+
+	assert_eq!(parse(PART_1), Err(JMAFormatParseError::Split));
+
+	asm.push(PART_1);
+	let joined = asm.push(PART_2).unwrap();
+	let eew = parse(&joined).unwrap();
+	let areas: Vec<&str> = eew.detail.as_ref().unwrap().area_info.iter()
+		.map(|a| a.area_name.as_str()).collect();
+
+	assert_eq!(areas, vec!{"大阪府南部", "奈良県", "京都府南部"});
+}
+
+#[test]
+fn it_should_discard_expired_parts()
+{
+	let mut asm = TelegramAssembler::new(Duration::from_millis(10));
+
+	assert_eq!(asm.push(PART_1), None);
+	sleep(Duration::from_millis(50));
+	assert_eq!(asm.push(PART_2), None);
+	assert_eq!(asm.pending_count(), 1);
+}
+
+#[test]
+fn it_should_not_join_parts_of_different_telegram_numbers()
+{
+	let mut asm = TelegramAssembler::new(Duration::from_secs(10));
+
+	// NCN106 instead of NCN006
+	let part_2 = String::from_utf8(PART_2.to_vec()).unwrap().replace("NCN006", "NCN106");
+
+	assert_eq!(asm.push(PART_1), None);
+	assert_eq!(asm.push(part_2.as_bytes()), None);
+	assert_eq!(asm.pending_count(), 2);
+}
@@ -0,0 +1,113 @@
+extern crate chrono;
+extern crate tina;
+
+use chrono::{Utc, TimeZone};
+
+use tina::*;
+
+mod eew_builder;
+use eew_builder::*;
+
+
+fn area(code: &str, name: &str, intensity: IntensityClass) -> AreaEEW
+{
+	AreaEEW {
+		area_code: code.to_owned(),
+		area_name: name.to_owned(),
+		minimum_intensity: intensity,
+		maximum_intensity: None,
+		reach_at: Some(Utc.ymd(2010, 1, 1).and_hms(0, 56, 9)),
+		warning_status: WarningStatus::Alert,
+		wave_status: WaveStatus::Unreached,
+	}
+}
+
+#[test]
+fn it_should_render_fields_and_helpers()
+{
+	let eew = EEWBuilder::new().build();
+	let template = Template::new("{{id}} #{{number}} {{phase}} {{detail.epicenter_name}} \
+		{{intensity detail.maximum_intensity}} {{magnitude detail.magnitude}} {{depth detail.depth}} \
+		({{position detail.epicenter}}) {{time occurred_at}}", Language::Japanese).unwrap();
+
+	let expected = "NDXXXX #10 forecast 奈良県 震度5弱 M5.9 10km (N34.4/E135.7) 09:55:59".to_owned();
+
+	assert_eq!(template.render(&eew, None, &[]), Ok(expected));
+}
+
+#[test]
+fn it_should_render_loops_over_areas()
+{
+	let eew = EEWBuilder::new()
+		.area_info(vec!{area("540", "奈良県", IntensityClass::FiveLower), area("521", "大阪府南部", IntensityClass::Four)})
+		.build();
+	let template = Template::new("{{#each detail.area_info}}{{area_name}}:{{intensity minimum_intensity}}\
+		({{time reach_at}}){{#unless @last}}, {{/unless}}{{/each}}", Language::English).unwrap();
+
+	let expected = "奈良県:Shindo 5-(09:56:09), 大阪府南部:Shindo 4(09:56:09)".to_owned();
+
+	assert_eq!(template.render(&eew, None, &[]), Ok(expected));
+}
+
+#[test]
+fn it_should_render_unknown_times()
+{
+	let mut unknown = area("540", "奈良県", IntensityClass::FiveLower);
+	unknown.reach_at = None;
+
+	let eew = EEWBuilder::new().area_info(vec!{unknown, area("521", "大阪府南部", IntensityClass::Four)}).build();
+	let template = Template::new("{{#each detail.area_info}}{{area_name}}({{time reach_at}}) {{/each}}",
+		Language::Japanese).unwrap();
+
+	let expected = "奈良県(--:--:--) 大阪府南部(09:56:09) ".to_owned();
+
+	assert_eq!(template.render(&eew, None, &[]), Ok(expected));
+}
+
+#[test]
+fn it_should_render_conditional_sections()
+{
+	let template = Template::new("{{#if test}}[TEST] {{/if}}{{#if detail}}{{header}}{{updown}}\
+		{{else}}cancelled{{/if}}", Language::Japanese).unwrap();
+
+	let eew1 = EEWBuilder::new().maximum_intensity(Some(IntensityClass::Three)).build();
+	let eew2 = EEWBuilder::new().kind(Kind::Trial).build();
+	let cancel = EEWBuilder::new().issue_pattern(IssuePattern::Cancel).kind(Kind::Cancel).detail_none().build();
+
+	assert_eq!(template.render(&eew2, Some(&eew1), &[]), Ok("[TEST] テスト配信 | 予報↑↑".to_owned()));
+	assert_eq!(template.render(&cancel, None, &[]), Ok("cancelled".to_owned()));
+}
+
+#[test]
+fn it_should_reject_invalid_templates()
+{
+	match Template::new("{{#if detail}}{{id}}", Language::Japanese) {
+		Err(TemplateError::InvalidSyntax(_)) => {},
+		other => panic!("unexpected result: {:?}", other),
+	}
+
+	let template = Template::new("{{position detail}}", Language::Japanese).unwrap();
+
+	match template.render(&EEWBuilder::new().build(), None, &[]) {
+		Err(TemplateError::RenderFailure(_)) => {},
+		other => panic!("unexpected result: {:?}", other),
+	}
+}
+
+#[test]
+fn it_should_replace_the_message_of_the_translator()
+{
+	let eew = EEWBuilder::new().build();
+	let translator = Translator::default().with_template("{{detail.epicenter_name}} {{footer}}").unwrap();
+
+	assert_eq!(translator.format_eew_oneline(&eew, None), Some("奈良県 第10報 NDXXXX".to_owned()));
+
+	let short = translator.format_eew_short(&eew, None).unwrap();
+	assert_eq!(short.0, "予報");
+	assert_eq!(short.1, "奈良県 第10報 NDXXXX");
+	assert_eq!(short.2, "第10報 NDXXXX");
+
+	// a broken template falls back to the default format
+	let broken = Translator::default().with_template("{{position detail}}").unwrap();
+	assert_eq!(broken.format_eew_oneline(&eew, None), ja_format_eew_oneline(&eew, None));
+}
@@ -1,6 +1,8 @@
 extern crate chrono;
 extern crate tina;
 
+use chrono::{Utc, TimeZone};
+
 use tina::*;
 
 mod eew_builder;
@@ -10,6 +12,8 @@ const DEF_COND: ValueCondition = ValueCondition {
 	first: None, succeeding: None, alert: None, last: None, cancel: None, drill: None, test: None,
 	phase_changed: None, epicenter_name_changed: None, magnitude_over: None, intensity_over: None,
 	intensity_up: None, intensity_down: None,
+	areas: None, area_intensity_over: None, area_alert: None, area_wave_status: None,
+	observers: Vec::new(), distance_under_km: None, s_wave_within_secs: None, estimated_intensity_at_site_over: None,
 };
 
 #[test]
@@ -223,3 +227,177 @@ fn it_should_handle_intensity_down_condition_with_value_2()
 	assert_eq!(cond.is_satisfied(&one_eew, Some(&two_eew)), false);
 	assert_eq!(cond.is_satisfied(&one_eew, Some(&three_eew)), true);
 }
+
+fn area(code: &str, name: &str, intensity: IntensityClass, alert: bool, wave_status: WaveStatus) -> AreaEEW
+{
+	AreaEEW {
+		area_code: code.to_owned(),
+		area_name: name.to_owned(),
+		minimum_intensity: intensity,
+		maximum_intensity: None,
+		reach_at: None,
+		warning_status: if alert { WarningStatus::Alert } else { WarningStatus::Forecast },
+		wave_status: wave_status,
+	}
+}
+
+fn area_eew() -> EEW
+{
+	EEWBuilder::new().area_info(vec!{
+		area("350", "東京都２３区", IntensityClass::FiveLower, true, WaveStatus::Unreached),
+		area("341", "千葉県北西部", IntensityClass::Four, false, WaveStatus::Reached),
+	}).build()
+}
+
+#[test]
+fn it_should_handle_areas_condition()
+{
+	let detail_none_eew = EEWBuilder::new().detail_none().build();
+	let eew = area_eew();
+
+	let tokyo = ValueCondition { areas: Some(vec!{"東京都２３区".to_owned()}), .. DEF_COND };
+	let osaka = ValueCondition { areas: Some(vec!{"大阪府北部".to_owned()}), .. DEF_COND };
+
+	assert_eq!(tokyo.is_satisfied(&eew, None), true);
+	assert_eq!(osaka.is_satisfied(&eew, None), false);
+	assert_eq!(tokyo.is_satisfied(&detail_none_eew, Some(&eew)), false);
+}
+
+#[test]
+fn it_should_handle_area_intensity_over_condition()
+{
+	let eew = area_eew();
+
+	let chiba_4 = ValueCondition { areas: Some(vec!{"千葉県北西部".to_owned()}),
+		area_intensity_over: Some(IntensityClass::Four), .. DEF_COND };
+	let chiba_5 = ValueCondition { areas: Some(vec!{"千葉県北西部".to_owned()}),
+		area_intensity_over: Some(IntensityClass::FiveLower), .. DEF_COND };
+	let any_5 = ValueCondition { area_intensity_over: Some(IntensityClass::FiveLower), .. DEF_COND };
+	let any_6 = ValueCondition { area_intensity_over: Some(IntensityClass::SixLower), .. DEF_COND };
+
+	assert_eq!(chiba_4.is_satisfied(&eew, None), true);
+	assert_eq!(chiba_5.is_satisfied(&eew, None), false);
+	assert_eq!(any_5.is_satisfied(&eew, None), true);
+	assert_eq!(any_6.is_satisfied(&eew, None), false);
+}
+
+#[test]
+fn it_should_handle_area_intensity_range()
+{
+	let mut ranged = area("350", "東京都２３区", IntensityClass::Four, false, WaveStatus::Unknown);
+	ranged.maximum_intensity = Some(IntensityClass::FiveUpper);
+	let eew = EEWBuilder::new().area_info(vec!{ranged}).build();
+
+	let cond = ValueCondition { area_intensity_over: Some(IntensityClass::FiveUpper), .. DEF_COND };
+	assert_eq!(cond.is_satisfied(&eew, None), true);
+}
+
+#[test]
+fn it_should_handle_area_alert_and_wave_status_conditions()
+{
+	let eew = area_eew();
+
+	let tokyo_alert = ValueCondition { areas: Some(vec!{"東京都２３区".to_owned()}),
+		area_alert: Some(true), .. DEF_COND };
+	let chiba_alert = ValueCondition { areas: Some(vec!{"千葉県北西部".to_owned()}),
+		area_alert: Some(true), .. DEF_COND };
+	let unreached = ValueCondition { area_wave_status: Some(WaveStatus::Unreached), .. DEF_COND };
+	let chiba_unreached = ValueCondition { areas: Some(vec!{"千葉県北西部".to_owned()}),
+		area_wave_status: Some(WaveStatus::Unreached), .. DEF_COND };
+
+	assert_eq!(tokyo_alert.is_satisfied(&eew, None), true);
+	assert_eq!(chiba_alert.is_satisfied(&eew, None), false);
+	assert_eq!(unreached.is_satisfied(&eew, None), true);
+	assert_eq!(chiba_unreached.is_satisfied(&eew, None), false);
+}
+
+#[test]
+fn it_should_match_areas_by_code()
+{
+	let eew = area_eew();
+
+	let tokyo = ValueCondition { areas: Some(vec!{"350".to_owned()}), .. DEF_COND };
+	let osaka = ValueCondition { areas: Some(vec!{"521".to_owned()}), .. DEF_COND };
+
+	assert_eq!(tokyo.is_satisfied(&eew, None), true);
+	assert_eq!(osaka.is_satisfied(&eew, None), false);
+}
+
+fn observer_eew() -> EEW
+{
+	// about 70km north of the observer below
+	EEWBuilder::new()
+		.epicenter((34.0 + 70.0 / 111.195, 135.0))
+		.depth(Some(0.0))
+		.occurred_at(Utc.ymd(2010, 1, 1).and_hms(0, 0, 0))
+		.issued_at(Utc.ymd(2010, 1, 1).and_hms(0, 0, 5))
+		.build()
+}
+
+fn observers() -> Vec<Observer>
+{
+	vec!{
+		Observer { name: "far".to_owned(), lat: 40.0, lon: 140.0, amplification: 1.0, travel_times: None },
+		Observer { name: "near".to_owned(), lat: 34.0, lon: 135.0, amplification: 1.0, travel_times: None },
+	}
+}
+
+#[test]
+fn it_should_handle_distance_under_km_condition()
+{
+	let eew = observer_eew();
+
+	let cond = ValueCondition { observers: observers(), distance_under_km: Some(80.0), .. DEF_COND };
+	assert_eq!(cond.is_satisfied(&eew, None), true);
+
+	let cond = ValueCondition { observers: observers(), distance_under_km: Some(60.0), .. DEF_COND };
+	assert_eq!(cond.is_satisfied(&eew, None), false);
+
+	let cond = ValueCondition { observers: observers()[..1].to_vec(), distance_under_km: Some(80.0), .. DEF_COND };
+	assert_eq!(cond.is_satisfied(&eew, None), false);
+
+	let cond = ValueCondition { distance_under_km: Some(80.0), .. DEF_COND };
+	assert_eq!(cond.is_satisfied(&eew, None), false);
+}
+
+#[test]
+fn it_should_handle_s_wave_within_secs_condition()
+{
+	let eew = observer_eew();
+
+	// the S-wave arrives 12.5s after the issue
+	let cond = ValueCondition { observers: observers(), s_wave_within_secs: Some(15.0), .. DEF_COND };
+	assert_eq!(cond.is_satisfied(&eew, None), true);
+
+	let cond = ValueCondition { observers: observers(), s_wave_within_secs: Some(10.0), .. DEF_COND };
+	assert_eq!(cond.is_satisfied(&eew, None), false);
+
+	let cancel = EEWBuilder::new().detail_none().build();
+	let cond = ValueCondition { observers: observers(), s_wave_within_secs: Some(15.0), .. DEF_COND };
+	assert_eq!(cond.is_satisfied(&cancel, None), false);
+}
+
+#[test]
+fn it_should_handle_estimated_intensity_at_site_over_condition()
+{
+	// M5.9 at the depth of 10km estimated as 4.8 on the bedrock at the epicenter
+	let eew = EEWBuilder::new().build();
+	let site = |amplification| Observer { name: "site".to_owned(), lat: 34.4, lon: 135.7, amplification: amplification, travel_times: None };
+
+	let cond = ValueCondition { observers: vec!{site(1.0)},
+		estimated_intensity_at_site_over: Some(IntensityClass::FiveLower), .. DEF_COND };
+	assert_eq!(cond.is_satisfied(&eew, None), true);
+
+	let cond = ValueCondition { observers: vec!{site(1.0)},
+		estimated_intensity_at_site_over: Some(IntensityClass::FiveUpper), .. DEF_COND };
+	assert_eq!(cond.is_satisfied(&eew, None), false);
+
+	let cond = ValueCondition { observers: vec!{site(2.0)},
+		estimated_intensity_at_site_over: Some(IntensityClass::FiveUpper), .. DEF_COND };
+	assert_eq!(cond.is_satisfied(&eew, None), true);
+
+	let eew = EEWBuilder::new().magnitude(None).build();
+	let cond = ValueCondition { observers: vec!{site(2.0)},
+		estimated_intensity_at_site_over: Some(IntensityClass::One), .. DEF_COND };
+	assert_eq!(cond.is_satisfied(&eew, None), false);
+}
@@ -0,0 +1,123 @@
+extern crate chrono;
+extern crate serde_yaml;
+extern crate tina;
+
+use tina::*;
+
+mod eew_builder;
+use eew_builder::*;
+
+const NESTED: &'static str = "
+- first: true
+- all:
+  - not: { test: true, drill: true }
+  - any:
+    - alert: true
+    - intensity_over: 5
+";
+
+fn parse(yaml: &str) -> Result<Vec<ConditionConfig>, String>
+{
+	serde_yaml::from_str(yaml).map_err(|e| e.to_string())
+}
+
+#[test]
+fn it_should_parse_nested_conditions()
+{
+	let conds = parse(NESTED).unwrap();
+
+	match conds[1] {
+		ConditionConfig::All { ref all } => {
+			match all[0] {
+				ConditionConfig::Not { ref not } => match **not {
+					ConditionConfig::Value(ref v) => assert_eq!((v.test, v.drill), (Some(true), Some(true))),
+					ref other => panic!("unexpected condition: {:?}", other),
+				},
+				ref other => panic!("unexpected condition: {:?}", other),
+			}
+			match all[1] {
+				ConditionConfig::Any { ref any } => assert_eq!(any.len(), 2),
+				ref other => panic!("unexpected condition: {:?}", other),
+			}
+		},
+		ref other => panic!("unexpected condition: {:?}", other),
+	}
+
+	match conds[0] {
+		ConditionConfig::Value(ref v) => {
+			assert_eq!(v.first, Some(true));
+			// omitted `drill` and `test` are defaulted when building the condition
+			assert_eq!((v.test, v.drill), (None, None));
+		},
+		ref other => panic!("unexpected condition: {:?}", other),
+	}
+}
+
+#[test]
+fn it_should_build_nested_conditions()
+{
+	let cond = build_yaml_condition(parse(NESTED).unwrap(), &[]);
+	let prev = EEWBuilder::new().maximum_intensity(Some(IntensityClass::Three)).build();
+
+	let eew = |warning_status, intensity| EEWBuilder::new()
+		.warning_status(warning_status).maximum_intensity(Some(intensity)).build();
+
+	// `first: true`
+	assert!(cond.is_satisfied(&eew(WarningStatus::Forecast, IntensityClass::Three), None));
+	// `all` of `not` and `any`
+	assert!(cond.is_satisfied(&eew(WarningStatus::Alert, IntensityClass::Three), Some(&prev)));
+	assert!(cond.is_satisfied(&eew(WarningStatus::Forecast, IntensityClass::FiveUpper), Some(&prev)));
+	assert!(! cond.is_satisfied(&eew(WarningStatus::Forecast, IntensityClass::FiveLower), Some(&prev)));
+
+	// `alert: true` in `any` rejects tests by default
+	let test = EEWBuilder::new().kind(Kind::Trial).warning_status(WarningStatus::Alert).build();
+	assert!(! cond.is_satisfied(&test, Some(&prev)));
+}
+
+#[test]
+fn it_should_reject_drills_and_tests_under_not()
+{
+	let cond = build_yaml_condition(parse("- not: { alert: true }").unwrap(), &[]);
+
+	let forecast = EEWBuilder::new().warning_status(WarningStatus::Forecast).build();
+	let drill = EEWBuilder::new().kind(Kind::Drill).warning_status(WarningStatus::Forecast).build();
+	let test = EEWBuilder::new().kind(Kind::Trial).warning_status(WarningStatus::Forecast).build();
+
+	assert!(cond.is_satisfied(&forecast, None));
+	assert!(! cond.is_satisfied(&drill, None));
+	assert!(! cond.is_satisfied(&test, None));
+
+	// explicitly allowed in the clause
+	let cond = build_yaml_condition(parse("- all: [{ test: true }, { not: { alert: true } }]").unwrap(), &[]);
+	assert!(cond.is_satisfied(&test, None));
+	assert!(! cond.is_satisfied(&drill, None));
+
+	// `not: { test: true }` rejects tests and drills are still rejected by default
+	let cond = build_yaml_condition(parse("- not: { test: true }").unwrap(), &[]);
+	assert!(cond.is_satisfied(&forecast, None));
+	assert!(! cond.is_satisfied(&test, None));
+	assert!(! cond.is_satisfied(&drill, None));
+}
+
+#[test]
+fn it_should_report_key_paths_of_invalid_conditions()
+{
+	let typo = "
+- all:
+  - not: { test: true }
+  - any:
+    - alert: true
+    - intensity_ovr: 5
+";
+	let not_list = "- any: { alert: true }";
+	let invalid_value = "- not: { alert: 1.5 }";
+
+	let err = parse(typo).unwrap_err();
+	assert!(err.starts_with("all[1].any[1]: unknown field `intensity_ovr`"), "{}", err);
+
+	let err = parse(not_list).unwrap_err();
+	assert!(err.starts_with("any: expected a list of conditions"), "{}", err);
+
+	let err = parse(invalid_value).unwrap_err();
+	assert!(err.starts_with("not: invalid type: floating point `1.5`, expected a boolean"), "{}", err);
+}